@@ -5,9 +5,11 @@ use colored::Colorize;
 use rusqlite::Connection;
 
 use crate::cli::{AgentFilters, AgentsCommand};
+use crate::config::settings::load_config;
+use crate::search::{dates, suggest};
 
 use super::indexer;
-use super::models::{AiSearchResult, AiSession, Source};
+use super::models::{self, AiSearchResult, AiSession, Source};
 use super::resume;
 use super::search::{self, Mode};
 use super::store::{self, Filter};
@@ -19,6 +21,8 @@ pub fn handle(command: Option<AgentsCommand>) -> Result<()> {
         filters: AgentFilters {
             source: None,
             project: None,
+            since: None,
+            until: None,
             limit: 20,
             no_index: false,
         },
@@ -28,7 +32,17 @@ pub fn handle(command: Option<AgentsCommand>) -> Result<()> {
             query,
             filters,
             fuzzy,
-        } => handle_search(&conn, &query, &filters, fuzzy),
+            hybrid,
+        } => {
+            let mode = if hybrid {
+                Mode::Hybrid
+            } else if fuzzy {
+                Mode::Fuzzy
+            } else {
+                Mode::Fts
+            };
+            handle_search(&conn, &query, &filters, mode)
+        }
         AgentsCommand::List { filters } => handle_list(&conn, &filters),
         AgentsCommand::Show { session } => handle_show(&conn, &session),
         AgentsCommand::Resume { session, dir, print } => {
@@ -39,7 +53,10 @@ pub fn handle(command: Option<AgentsCommand>) -> Result<()> {
 }
 
 fn parse_source(raw: &str) -> Result<Source> {
-    Source::parse(raw).ok_or_else(|| anyhow!("Unknown source `{}`. Use claude or codex.", raw))
+    Source::parse(raw).ok_or_else(|| {
+        let names: Vec<&str> = Source::all().iter().map(|s| s.as_str()).collect();
+        anyhow!("Unknown source `{}`. Use one of: {}.", raw, names.join(", "))
+    })
 }
 
 /// Keep the index honest before answering. A warm reconcile is well under a
@@ -54,7 +71,7 @@ fn refresh_index(conn: &Connection, skip: bool) -> Result<()> {
         eprintln!(
             "  {} {}",
             "●".dimmed(),
-            "First run — indexing Claude Code and Codex transcripts...".dimmed()
+            "First run — indexing agent transcripts...".dimmed()
         );
     }
 
@@ -82,7 +99,11 @@ fn build_filter(filters: &AgentFilters) -> Result<Filter> {
     Ok(Filter {
         source: filters.source.as_deref().map(parse_source).transpose()?,
         project: filters.project.clone(),
+        since: filters.since.as_deref().map(dates::parse).transpose()?.map(|span| span.start),
+        until: filters.until.as_deref().map(dates::parse).transpose()?.map(|span| span.end),
         limit: filters.limit.max(1),
+        ranking: load_config()?.search.ranking,
+        ..Default::default()
     })
 }
 
@@ -113,6 +134,13 @@ fn handle_index(conn: &Connection, force: bool, source: Option<&str>) -> Result<
         "│".dimmed(),
         report.chunks.to_string().cyan()
     );
+    if report.redacted > 0 {
+        println!(
+            "  {} {} secrets redacted before indexing",
+            "│".dimmed(),
+            report.redacted.to_string().yellow()
+        );
+    }
 
     for (path, error) in &report.failed {
         println!("  {} {} {}", "│".dimmed(), "skipped".red(), path.dimmed());
@@ -128,12 +156,67 @@ fn handle_search(
     conn: &Connection,
     query: &str,
     filters: &AgentFilters,
-    fuzzy: bool,
+    mode: Mode,
 ) -> Result<()> {
+    let filter = Filter {
+        query: crate::search::query::parse(query)?,
+        ..build_filter(filters)?
+    };
     refresh_index(conn, filters.no_index)?;
-    let filter = build_filter(filters)?;
-    let mode = if fuzzy { Mode::Fuzzy } else { Mode::Fts };
-    let (results, used) = search::search(conn, query, &filter, mode)?;
+
+    // Only `key:value` filters: list what they let through, newest first.
+    if !filter.query.has_text() {
+        let sessions = store::list_sessions(conn, &filter)?;
+        if sessions.is_empty() {
+            empty_note(conn, &format!("No sessions match \"{}\".", query))?;
+            return Ok(());
+        }
+        println!();
+        println!(
+            "  {} {}  {}",
+            "◉".cyan(),
+            format!("Sessions: \"{}\"", query).bold(),
+            format!("{} sessions", sessions.len()).dimmed()
+        );
+        println!("  {}", "─".repeat(60).dimmed());
+        for session in &sessions {
+            print_session_line(session);
+        }
+        println!();
+        return Ok(());
+    }
+
+    let (mut results, mut used) = search::search(conn, &filter, mode)?;
+    let mut heading = format!("Sessions: \"{}\"", query);
+
+    if results.is_empty() {
+        match suggest::suggest(conn, query, &filter.query)? {
+            Some(correction) if load_config()?.search.autocorrect => {
+                let retry = Filter {
+                    query: crate::search::query::parse(&correction.input)?,
+                    ..filter.clone()
+                };
+                (results, used) = search::search(conn, &retry, mode)?;
+                heading = format!("Sessions: \"{}\"  (nothing for \"{}\")", correction.input, query);
+            }
+            Some(correction) => {
+                println!(
+                    "\n  {} {}",
+                    "●".dimmed(),
+                    format!("No sessions match \"{}\".", query).dimmed()
+                );
+                println!(
+                    "  {} Did you mean {}?  {}",
+                    "●".yellow(),
+                    correction.words().bold(),
+                    format!("recall agents search \"{}\"", correction.input).dimmed()
+                );
+                println!();
+                return Ok(());
+            }
+            None => {}
+        }
+    }
 
     if results.is_empty() {
         empty_note(conn, &format!("No sessions match \"{}\".", query))?;
@@ -144,7 +227,7 @@ fn handle_search(
     println!(
         "  {} {}  {}",
         "◉".cyan(),
-        format!("Sessions: \"{}\"", query).bold(),
+        heading.bold(),
         format!("{} results · {}", results.len(), used.label()).dimmed()
     );
     println!("  {}", "─".repeat(60).dimmed());
@@ -253,8 +336,14 @@ fn handle_resume(
     };
     let spec = resume::resume_command(&session, dir);
 
+    // Imported chats have a site, not a directory, for a project.
+    let in_dir = std::path::Path::new(&spec.cwd).is_dir();
     if print_only {
-        println!("cd {} && {}", spec.cwd, spec.display());
+        if in_dir {
+            println!("cd {} && {}", spec.cwd, spec.display());
+        } else {
+            println!("{}", spec.display());
+        }
         return Ok(());
     }
 
@@ -263,7 +352,11 @@ fn handle_resume(
         "  {} {}  {}",
         "▶".green(),
         spec.display().bold(),
-        format!("in {}", spec.cwd).dimmed()
+        if in_dir {
+            format!("in {}", spec.cwd).dimmed()
+        } else {
+            "".dimmed()
+        }
     );
     println!();
 
@@ -338,8 +431,33 @@ fn empty_note(conn: &Connection, message: &str) -> Result<()> {
 
 fn print_result(result: &AiSearchResult) {
     print_session_line(&result.session);
-    let snippet = result.snippet.replace('\n', " ");
-    println!("      {}", truncate(&snippet, 100).dimmed());
+    println!("      {}", render_snippet(&result.snippet, 100));
+}
+
+/// A search snippet on one line, at most `max` characters, dimmed with the
+/// matched words picked out.
+pub fn render_snippet(snippet: &str, max: usize) -> String {
+    let flat = snippet.replace('\n', " ");
+    let mut left = max;
+    let mut out = String::new();
+    for (piece, matched) in models::split_matches(&flat) {
+        if left == 0 {
+            break;
+        }
+        let count = piece.chars().count();
+        let piece = if count > left {
+            truncate(&piece, left)
+        } else {
+            piece
+        };
+        left = left.saturating_sub(count);
+        if matched {
+            out.push_str(&piece.yellow().bold().to_string());
+        } else {
+            out.push_str(&piece.dimmed().to_string());
+        }
+    }
+    out
 }
 
 fn print_session_line(session: &AiSession) {
@@ -1,4 +1,5 @@
-//! Indexing and search for AI coding assistant sessions (Claude Code, Codex).
+//! Indexing and search for AI coding assistant sessions (Claude Code, Codex,
+//! Gemini CLI, Aider) and for web chats imported from ChatGPT and Claude.ai.
 //!
 //! Transcripts stay where their tool wrote them; recall reads them, splits each
 //! conversation into chunks, and indexes those chunks in the same SQLite +
@@ -7,6 +8,7 @@
 
 pub mod chunker;
 pub mod commands;
+pub mod import;
 pub mod indexer;
 pub mod models;
 pub mod resume;
@@ -35,6 +37,40 @@ pub fn sessions_dir_codex() -> PathBuf {
     override_dir("RECALL_CODEX_DIR").unwrap_or_else(|| home().join(".codex").join("sessions"))
 }
 
+/// Where Gemini CLI keeps its per-project chats and checkpoints.
+/// `RECALL_GEMINI_DIR` overrides it.
+pub fn sessions_dir_gemini() -> PathBuf {
+    override_dir("RECALL_GEMINI_DIR").unwrap_or_else(|| home().join(".gemini").join("tmp"))
+}
+
+/// The directories to look for Aider chat logs under, from `[aider] roots`.
+/// `RECALL_AIDER_ROOTS`, a path list like `PATH`, overrides them.
+pub fn aider_roots() -> Vec<PathBuf> {
+    if let Some(roots) = std::env::var_os("RECALL_AIDER_ROOTS") {
+        return std::env::split_paths(&roots)
+            .filter(|root| !root.as_os_str().is_empty())
+            .collect();
+    }
+    let roots = crate::config::settings::load_config()
+        .map(|config| config.aider.roots)
+        .unwrap_or_default();
+    roots
+        .iter()
+        .filter(|root| !root.trim().is_empty())
+        .map(|root| match root.strip_prefix('~') {
+            Some(rest) => home().join(rest.trim_start_matches('/')),
+            None => PathBuf::from(root),
+        })
+        .collect()
+}
+
+/// Where `recall import chat-export` keeps the conversations it imports.
+/// `RECALL_IMPORTS_DIR` overrides it.
+pub fn imports_dir() -> PathBuf {
+    override_dir("RECALL_IMPORTS_DIR")
+        .unwrap_or_else(|| crate::config::settings::recall_dir().join("imports"))
+}
+
 fn override_dir(variable: &str) -> Option<PathBuf> {
     std::env::var_os(variable)
         .filter(|value| !value.is_empty())
@@ -1,20 +1,47 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use super::sources;
 
 /// An AI coding assistant whose sessions recall can index.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Source {
     Claude,
     Codex,
+    Gemini,
+    Aider,
+    /// ChatGPT and Claude.ai conversations, from `recall import chat-export`.
+    Chat,
+    /// A tool described by a `[[sources]]` entry in config.toml, by name.
+    Custom(&'static str),
 }
 
 impl Source {
-    pub const ALL: [Source; 2] = [Source::Claude, Source::Codex];
+    pub const BUILTIN: [Source; 5] = [
+        Source::Claude,
+        Source::Codex,
+        Source::Gemini,
+        Source::Aider,
+        Source::Chat,
+    ];
+
+    /// The built-in sources, then the configured ones.
+    pub fn all() -> Vec<Source> {
+        Source::BUILTIN
+            .into_iter()
+            .chain(sources::configured().iter().map(|s| Source::Custom(&s.name)))
+            .collect()
+    }
 
     pub fn as_str(&self) -> &'static str {
         match self {
             Source::Claude => "claude",
             Source::Codex => "codex",
+            Source::Gemini => "gemini",
+            Source::Aider => "aider",
+            Source::Chat => "chat",
+            Source::Custom(name) => name,
         }
     }
 
@@ -22,18 +49,75 @@ impl Source {
         match self {
             Source::Claude => "Claude Code",
             Source::Codex => "Codex",
+            Source::Gemini => "Gemini CLI",
+            Source::Aider => "Aider",
+            Source::Chat => "Web chats",
+            Source::Custom(name) => sources::config_for(name)
+                .and_then(|s| s.label.as_deref())
+                .unwrap_or(name),
         }
     }
 
     pub fn parse(s: &str) -> Option<Source> {
+        Source::parse_builtin(s).or_else(|| {
+            sources::configured()
+                .iter()
+                .find(|source| source.name.eq_ignore_ascii_case(s))
+                .map(|source| Source::Custom(&source.name))
+        })
+    }
+
+    /// The source a name stored in the index refers to. A name that is no
+    /// longer configured — its `[[sources]]` entry removed or renamed, or
+    /// config.toml unreadable — stays a source of that name, which nothing
+    /// can re-read or resume, rather than passing for another tool.
+    pub fn from_stored(name: &str) -> Source {
+        Source::parse(name).unwrap_or_else(|| Source::Custom(intern(name)))
+    }
+
+    pub fn parse_builtin(s: &str) -> Option<Source> {
         match s.to_ascii_lowercase().as_str() {
             "claude" | "claude-code" | "claudecode" => Some(Source::Claude),
             "codex" => Some(Source::Codex),
+            "gemini" | "gemini-cli" => Some(Source::Gemini),
+            "aider" => Some(Source::Aider),
+            "chat" | "chats" | "web" => Some(Source::Chat),
             _ => None,
         }
     }
 }
 
+/// `name` with a `'static` lifetime, leaked once per distinct name.
+fn intern(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(leaked);
+            leaked
+        }
+    }
+}
+
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Source::parse(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown source `{}`", name)))
+    }
+}
+
 /// One AI assistant conversation, as discovered on disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiSession {
@@ -48,7 +132,7 @@ pub struct AiSession {
     /// else the opening prompt.
     pub title: Option<String>,
     /// A name the user saved for this session in the tool itself. Claude Code
-    /// records these; Codex has no equivalent today.
+    /// records these, as do Gemini CLI checkpoints; Codex has no equivalent today.
     pub custom_name: Option<String>,
     /// Milliseconds since epoch, matching the rest of recall's timestamps.
     pub started_at: i64,
@@ -96,6 +180,48 @@ pub struct Chunk {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiSearchResult {
     pub session: AiSession,
+    /// Matched words sit between [`MATCH_START`] and [`MATCH_END`].
     pub snippet: String,
     pub rank: f64,
 }
+
+/// Marks where a matched word starts in a snippet. Control characters, so
+/// they never collide with text and each front end draws them its own way.
+pub const MATCH_START: char = '\u{2}';
+/// Marks where a matched word ends.
+pub const MATCH_END: char = '\u{3}';
+
+/// A snippet in pieces, each flagged as matched or not, markers removed.
+pub fn split_matches(snippet: &str) -> Vec<(String, bool)> {
+    let mut pieces: Vec<(String, bool)> = Vec::new();
+    let mut current = String::new();
+    let mut matched = false;
+    for c in snippet.chars() {
+        let toggle = match c {
+            MATCH_START => Some(true),
+            MATCH_END => Some(false),
+            _ => None,
+        };
+        match toggle {
+            Some(next) => {
+                if !current.is_empty() {
+                    pieces.push((std::mem::take(&mut current), matched));
+                }
+                matched = next;
+            }
+            None => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        pieces.push((current, matched));
+    }
+    pieces
+}
+
+/// The snippet as plain text.
+pub fn strip_matches(snippet: &str) -> String {
+    snippet
+        .chars()
+        .filter(|c| *c != MATCH_START && *c != MATCH_END)
+        .collect()
+}
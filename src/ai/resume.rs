@@ -5,6 +5,7 @@ use std::path::Path;
 use std::process::Command;
 
 use super::models::{AiSession, Source};
+use super::sources::{self, gemini};
 
 /// A command line plus the directory it should run in.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,7 +17,17 @@ pub struct CommandSpec {
 impl CommandSpec {
     /// A copy-pasteable rendering of the command.
     pub fn display(&self) -> String {
-        self.args.join(" ")
+        self.args
+            .iter()
+            .map(|arg| {
+                if arg.contains(char::is_whitespace) {
+                    format!("'{}'", arg.replace('\'', r"'\''"))
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 }
 
@@ -33,6 +44,29 @@ pub fn resume_command(session: &AiSession, dir: Option<&str>) -> CommandSpec {
             "resume".to_string(),
             session.session_id.clone(),
         ],
+        // A saved checkpoint is reopened from inside a fresh session; an
+        // ordinary chat by its id.
+        Source::Gemini => match gemini::checkpoint_tag(Path::new(&session.file_path)) {
+            Some(tag) => vec![
+                "gemini".to_string(),
+                "--prompt-interactive".to_string(),
+                format!("/chat resume {}", tag),
+            ],
+            None => vec![
+                "gemini".to_string(),
+                "--resume".to_string(),
+                session.session_id.clone(),
+            ],
+        },
+        // Aider keeps one chat log per repo, so there is no single run to
+        // name; it reopens in the repo with that log loaded.
+        Source::Aider => vec!["aider".to_string(), "--restore-chat-history".to_string()],
+        // An imported web chat has no tool here to go back to.
+        Source::Chat => show_command(session),
+        Source::Custom(name) => match sources::config_for(name).and_then(|s| s.resume.as_deref()) {
+            Some(template) => fill_template(template, session),
+            None => show_command(session),
+        },
     };
 
     CommandSpec {
@@ -41,6 +75,27 @@ pub fn resume_command(session: &AiSession, dir: Option<&str>) -> CommandSpec {
     }
 }
 
+/// A configured resume command, split into words before `{id}`, `{cwd}` and
+/// `{file}` are filled in, so a value with spaces stays one argument.
+fn fill_template(template: &str, session: &AiSession) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|word| {
+            word.replace("{id}", &session.session_id)
+                .replace("{cwd}", &session.project)
+                .replace("{file}", &session.file_path)
+        })
+        .collect()
+}
+
+/// For a session no tool can reopen: print it instead.
+fn show_command(session: &AiSession) -> Vec<String> {
+    let recall = std::env::current_exe()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "recall".to_string());
+    vec![recall, "agents".to_string(), "show".to_string(), session.uid.clone()]
+}
+
 /// Replace the current process with the resume command, so the assistant owns
 /// the terminal exactly as if it had been launched directly.
 pub fn exec(spec: &CommandSpec) -> Result<()> {
@@ -111,6 +166,41 @@ mod tests {
         assert_eq!(spec.args, vec!["codex", "resume", "abc123"]);
     }
 
+    #[test]
+    fn gemini_resumes_chats_by_id_and_checkpoints_by_tag() {
+        let spec = resume_command(&session(Source::Gemini), None);
+        assert_eq!(spec.args, vec!["gemini", "--resume", "abc123"]);
+
+        let mut saved = session(Source::Gemini);
+        saved.file_path = "/home/me/.gemini/tmp/ab12/checkpoint-release%20plan.json".into();
+        let spec = resume_command(&saved, None);
+        assert_eq!(spec.args, vec!["gemini", "--prompt-interactive", "/chat resume release plan"]);
+        assert_eq!(spec.display(), "gemini --prompt-interactive '/chat resume release plan'");
+    }
+
+    #[test]
+    fn aider_reopens_in_its_repo_with_the_chat_restored() {
+        let spec = resume_command(&session(Source::Aider), None);
+        assert_eq!(spec.args, vec!["aider", "--restore-chat-history"]);
+        assert_eq!(spec.cwd, "/repos/thing");
+    }
+
+    #[test]
+    fn templates_fill_in_the_session_without_splitting_values() {
+        let mut custom = session(Source::Custom("acme"));
+        custom.project = "/repos/my thing".into();
+        assert_eq!(
+            fill_template("acme open --session {id} --dir {cwd}", &custom),
+            vec!["acme", "open", "--session", "abc123", "--dir", "/repos/my thing"]
+        );
+    }
+
+    #[test]
+    fn a_source_with_no_resume_command_shows_the_transcript() {
+        let spec = resume_command(&session(Source::Custom("acme")), None);
+        assert_eq!(spec.args[1..], ["agents", "show", "acme:abc123"]);
+    }
+
     #[test]
     fn an_explicit_directory_wins_over_the_project() {
         let spec = resume_command(&session(Source::Claude), Some("/elsewhere"));
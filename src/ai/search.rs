@@ -2,9 +2,11 @@
 
 use anyhow::Result;
 use rusqlite::Connection;
+use std::collections::HashMap;
 
 use super::models::AiSearchResult;
 use super::store::{self, Filter};
+use crate::embed::{self, Embedder};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
@@ -12,6 +14,9 @@ pub enum Mode {
     Fts,
     /// Case-insensitive substring matching, for fragments FTS5 can't tokenize.
     Fuzzy,
+    /// BM25 blended with nearness to the query's meaning, from the local
+    /// embedding model.
+    Hybrid,
 }
 
 impl Mode {
@@ -19,6 +24,7 @@ impl Mode {
         match self {
             Mode::Fts => "full-text",
             Mode::Fuzzy => "fuzzy",
+            Mode::Hybrid => "hybrid",
         }
     }
 }
@@ -27,16 +33,19 @@ impl Mode {
 /// it finds nothing, so a half-remembered fragment still lands somewhere.
 pub fn search(
     conn: &Connection,
-    query: &str,
     filter: &Filter,
     mode: Mode,
 ) -> Result<(Vec<AiSearchResult>, Mode)> {
     match mode {
-        Mode::Fuzzy => Ok((store::search_fuzzy(conn, query, filter)?, Mode::Fuzzy)),
+        Mode::Fuzzy => Ok((store::search_fuzzy(conn, filter)?, Mode::Fuzzy)),
+        Mode::Hybrid => {
+            let embedder = embed::configured()?;
+            Ok((search_hybrid(conn, filter, embedder.as_ref())?, Mode::Hybrid))
+        }
         Mode::Fts => {
-            let results = store::search(conn, query, filter)?;
+            let results = store::search(conn, filter)?;
             if results.is_empty() {
-                let fuzzy = store::search_fuzzy(conn, query, filter)?;
+                let fuzzy = store::search_fuzzy(conn, filter)?;
                 if !fuzzy.is_empty() {
                     return Ok((fuzzy, Mode::Fuzzy));
                 }
@@ -45,3 +54,133 @@ pub fn search(
         }
     }
 }
+
+/// Sessions that match the words or the meaning of the query, the ones that
+/// do both first. A session found only by meaning shows the opening of its
+/// nearest chunk.
+pub fn search_hybrid(
+    conn: &Connection,
+    filter: &Filter,
+    embedder: &dyn Embedder,
+) -> Result<Vec<AiSearchResult>> {
+    let text = filter.query.text();
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let words = store::search(conn, filter)?;
+    let vector = embed::embed_query(embedder, &text)?;
+    let near = embed::store::nearest_sessions(conn, embedder.model(), &vector, filter)?;
+
+    let ranked = embed::blend(&[
+        words.iter().map(|hit| hit.session.uid.clone()).collect(),
+        near.iter().map(|hit| hit.session.uid.clone()).collect(),
+    ]);
+    // A word match's snippet shows the words; prefer it.
+    let mut hits: HashMap<String, AiSearchResult> = near
+        .into_iter()
+        .chain(words)
+        .map(|hit| (hit.session.uid.clone(), hit))
+        .collect();
+
+    Ok(ranked
+        .into_iter()
+        .take(filter.limit)
+        .filter_map(|(uid, score)| {
+            let mut hit = hits.remove(&uid)?;
+            hit.rank = -score;
+            Some(hit)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::{session_uid, AiSession, Chunk, Source};
+    use crate::embed::tests::Synonyms;
+
+    fn indexed(conversations: &[(&str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        for (at, (id, text)) in conversations.iter().enumerate() {
+            let uid = session_uid(Source::Claude, id);
+            store::upsert_session(
+                &conn,
+                &AiSession {
+                    uid: uid.clone(),
+                    source: Source::Claude,
+                    session_id: id.to_string(),
+                    project: "/p".into(),
+                    title: None,
+                    started_at: at as i64,
+                    last_activity: at as i64,
+                    model: None,
+                    message_count: 1,
+                    file_path: format!("/tmp/{}.jsonl", id),
+                    file_mtime: 0,
+                    file_size: 0,
+                    custom_name: None,
+                },
+                0,
+            )
+            .unwrap();
+            store::insert_chunk(
+                &conn,
+                &Chunk {
+                    chunk_id: format!("{}-0", id),
+                    session_uid: uid,
+                    source: Source::Claude,
+                    project: "/p".into(),
+                    title: None,
+                    timestamp: at as i64,
+                    text: text.to_string(),
+                },
+            )
+            .unwrap();
+        }
+        embed::backfill(&conn, &Synonyms, 8, |_| {}).unwrap();
+        conn
+    }
+
+    fn searching(text: &str) -> Filter {
+        Filter {
+            query: crate::search::query::parse(text).unwrap(),
+            ..Filter::with_limit(10)
+        }
+    }
+
+    #[test]
+    fn hybrid_search_finds_the_same_idea_in_other_words() {
+        let conn = indexed(&[
+            ("backoff", "the tests fail with intermittent backoff failures"),
+            ("deploy", "rolling out the release to production"),
+        ]);
+        let filter = searching("flaky retries");
+        assert!(store::search(&conn, &filter).unwrap().is_empty());
+
+        let hits = search_hybrid(&conn, &filter, &Synonyms).unwrap();
+        assert_eq!(hits[0].session.session_id, "backoff");
+        assert!(hits[0].snippet.starts_with("the tests fail"));
+    }
+
+    #[test]
+    fn hybrid_search_puts_word_and_meaning_matches_first() {
+        let conn = indexed(&[
+            ("words", "flaky snapshot test in the parser"),
+            ("both", "flaky retries when postgres restarts"),
+            ("meaning", "sporadic backoff in the queue worker"),
+        ]);
+        let hits = search_hybrid(&conn, &searching("flaky retries"), &Synonyms).unwrap();
+        assert_eq!(hits[0].session.session_id, "both");
+        assert!(hits[0].snippet.contains("flaky"), "the word match's snippet wins");
+        assert_eq!(hits.len(), 3);
+    }
+
+    #[test]
+    fn hybrid_search_honours_filters() {
+        let conn = indexed(&[("backoff", "intermittent backoff failures")]);
+        let hits = search_hybrid(&conn, &searching("flaky retries source:codex"), &Synonyms).unwrap();
+        assert!(hits.is_empty());
+    }
+}
@@ -0,0 +1,422 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::ai::aider_roots;
+use crate::ai::models::{session_uid, AiSession, Message, Role, Source};
+use crate::ai::sources::{file_mtime, Conversation, SessionSource};
+
+/// Aider appends every conversation in a repo to one markdown log at its root,
+/// each starting with a `# aider chat started at` line. Every such run is a
+/// session here.
+pub struct AiderSource {
+    roots: Vec<PathBuf>,
+}
+
+impl AiderSource {
+    pub fn new() -> Self {
+        Self {
+            roots: aider_roots(),
+        }
+    }
+}
+
+const HISTORY_FILE: &str = ".aider.chat.history.md";
+/// What the user typed, one timestamped entry per prompt.
+const INPUT_HISTORY_FILE: &str = ".aider.input.history";
+const SESSION_HEADER: &str = "# aider chat started at ";
+/// How far below a root to look for repos: `~/code/org/repo` is two levels.
+const MAX_DEPTH: usize = 5;
+
+/// One run of aider within a chat log.
+struct Section<'a> {
+    session_id: String,
+    started_at: i64,
+    /// Everything after the header line, up to the next one.
+    body: &'a str,
+    /// Bytes the section takes in the file, header included.
+    len: usize,
+}
+
+/// Aider writes times in local time, without a zone.
+fn local_millis(naive: &NaiveDateTime) -> Option<i64> {
+    Local
+        .from_local_datetime(naive)
+        .earliest()
+        .map(|dt| dt.timestamp_millis())
+}
+
+/// Split a chat log into its runs. Ids are the start time and the repo, as in
+/// `20260301-100000@shop`, so they survive the file growing.
+fn sections<'a>(text: &'a str, repo: &str) -> Vec<Section<'a>> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.starts_with(SESSION_HEADER) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(text.len());
+        let section = &text[start..end];
+        let (header, body) = section.split_once('\n').unwrap_or((section, ""));
+        let stamp = header[SESSION_HEADER.len()..].trim();
+        let Ok(naive) = NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S") else {
+            continue;
+        };
+        let Some(started_at) = local_millis(&naive) else {
+            continue;
+        };
+        let mut session_id = format!("{}@{}", naive.format("%Y%m%d-%H%M%S"), repo);
+        // Two runs started within the same second.
+        let same = sections
+            .iter()
+            .filter(|s| s.session_id.starts_with(&session_id))
+            .count();
+        if same > 0 {
+            session_id = format!("{}-{}", session_id, same + 1);
+        }
+        sections.push(Section {
+            session_id,
+            started_at,
+            body,
+            len: end - start,
+        });
+    }
+    sections
+}
+
+/// When each prompt was typed, from `.aider.input.history`, oldest first.
+fn input_times(repo_dir: &Path) -> Vec<i64> {
+    let Ok(text) = fs::read_to_string(repo_dir.join(INPUT_HISTORY_FILE)) else {
+        return Vec::new();
+    };
+    let mut times: Vec<i64> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("# "))
+        .filter_map(|stamp| {
+            NaiveDateTime::parse_from_str(stamp.trim(), "%Y-%m-%d %H:%M:%S%.f").ok()
+        })
+        .filter_map(|naive| local_millis(&naive))
+        .collect();
+    times.sort_unstable();
+    times
+}
+
+/// The model a run used, from the banner aider prints as it starts.
+fn model_in(body: &str) -> Option<String> {
+    body.lines()
+        .filter_map(|line| {
+            line.strip_prefix("> Main model: ")
+                .or_else(|| line.strip_prefix("> Model: "))
+        })
+        .find_map(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+}
+
+/// A prompt as the conversation saw it. Most slash commands only manage the
+/// chat (`/add`, `/drop`, `/undo`) and are dropped; the ones that carry a
+/// question keep it.
+fn prompt_text(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() || input == "<blank>" {
+        return None;
+    }
+    if !input.starts_with('/') {
+        return Some(input.to_string());
+    }
+    let (command, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    match command {
+        "/ask" | "/code" | "/architect" => {
+            let rest = rest.trim();
+            (!rest.is_empty()).then(|| rest.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// User and assistant turns of one run. Prompts are the `####` lines;
+/// aider's own notices are quoted with `>`; the rest is the model's reply.
+/// Inside a reply's code fences every line is the reply's, edit markers
+/// such as `>>>>>>> REPLACE` included.
+fn messages_in(body: &str) -> Vec<Message> {
+    let mut messages = Vec::new();
+    let mut role: Option<Role> = None;
+    let mut buffer = String::new();
+    let mut in_fence = false;
+
+    fn flush(messages: &mut Vec<Message>, role: Option<Role>, buffer: &mut String) {
+        let text = std::mem::take(buffer);
+        let text = match role {
+            Some(Role::User) => prompt_text(&text),
+            Some(Role::Assistant) => Some(text.trim().to_string()).filter(|t| !t.is_empty()),
+            None => None,
+        };
+        if let (Some(role), Some(text)) = (role, text) {
+            messages.push(Message {
+                role,
+                text,
+                timestamp: None,
+                tool_names: Vec::new(),
+            });
+        }
+    }
+
+    for line in body.lines() {
+        if in_fence {
+            in_fence = !line.trim_start().starts_with("```");
+            buffer.push_str(line);
+            buffer.push('\n');
+            continue;
+        }
+        let prompt = line
+            .strip_prefix("#### ")
+            .or_else(|| (line.trim_end() == "####").then_some(""));
+        if let Some(prompt) = prompt {
+            if role != Some(Role::User) {
+                flush(&mut messages, role, &mut buffer);
+                role = Some(Role::User);
+            }
+            buffer.push_str(prompt.trim_end());
+            buffer.push('\n');
+        } else if line.starts_with('>') {
+            flush(&mut messages, role, &mut buffer);
+            role = None;
+        } else if line.trim().is_empty() {
+            if role == Some(Role::Assistant) {
+                buffer.push('\n');
+            }
+        } else {
+            if role != Some(Role::Assistant) {
+                flush(&mut messages, role, &mut buffer);
+                role = Some(Role::Assistant);
+            }
+            in_fence = line.trim_start().starts_with("```");
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+    }
+    flush(&mut messages, role, &mut buffer);
+    messages
+}
+
+fn skip_dir(name: &str) -> bool {
+    name.starts_with('.') || matches!(name, "node_modules" | "target" | "venv")
+}
+
+fn find_history_files(roots: &[PathBuf]) -> BTreeSet<PathBuf> {
+    roots
+        .iter()
+        .flat_map(|root| {
+            WalkDir::new(root)
+                .max_depth(MAX_DEPTH)
+                .into_iter()
+                .filter_entry(|e| {
+                    e.depth() == 0
+                        || !e.file_type().is_dir()
+                        || !e.file_name().to_str().is_some_and(skip_dir)
+                })
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file() && e.file_name() == HISTORY_FILE)
+                .map(|e| e.into_path())
+        })
+        .collect()
+}
+
+fn repo_name(repo_dir: &Path) -> String {
+    repo_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("repo")
+        .to_string()
+}
+
+fn read_log(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+impl SessionSource for AiderSource {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        let mut sessions = Vec::new();
+
+        for path in find_history_files(&self.roots) {
+            let metadata = fs::metadata(&path)?;
+            let mtime = file_mtime(&metadata);
+            let repo_dir = path.parent().unwrap_or(Path::new("/"));
+            let text = read_log(&path)?;
+            let sections = sections(&text, &repo_name(repo_dir));
+            let typed = input_times(repo_dir);
+
+            for (i, section) in sections.iter().enumerate() {
+                let next = sections.get(i + 1).map(|s| s.started_at);
+                let is_last = next.is_none();
+                let last_typed = typed
+                    .iter()
+                    .filter(|&&t| t >= section.started_at && next.is_none_or(|n| t < n))
+                    .max()
+                    .copied();
+                let mut last_activity = last_typed.unwrap_or(section.started_at);
+                if is_last {
+                    last_activity = last_activity.max(mtime);
+                }
+
+                sessions.push(AiSession {
+                    uid: session_uid(Source::Aider, &section.session_id),
+                    source: Source::Aider,
+                    session_id: section.session_id.clone(),
+                    project: repo_dir.to_string_lossy().to_string(),
+                    title: None,
+                    custom_name: None,
+                    started_at: section.started_at,
+                    last_activity,
+                    model: model_in(section.body),
+                    message_count: 0,
+                    file_path: path.to_string_lossy().to_string(),
+                    // Only the newest run grows, so earlier ones fingerprint
+                    // by their own extent and are not re-read on every append.
+                    file_mtime: if is_last { mtime } else { section.started_at },
+                    file_size: section.len as i64,
+                });
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    fn load_conversation(&self, session: &AiSession) -> Result<Conversation> {
+        let path = Path::new(&session.file_path);
+        let text = read_log(path)?;
+        let repo = repo_name(path.parent().unwrap_or(Path::new("/")));
+        let section = sections(&text, &repo)
+            .into_iter()
+            .find(|s| s.session_id == session.session_id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} is no longer in {}",
+                    session.session_id,
+                    session.file_path
+                )
+            })?;
+
+        Ok(Conversation {
+            messages: messages_in(section.body),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+# aider chat started at 2026-03-01 10:00:00
+
+> Aider v0.82.0
+> Main model: anthropic/claude-sonnet-4 with diff edit format
+> Git repo: .git with 112 files
+
+#### /add src/cart.py
+
+> Added src/cart.py to the chat
+
+#### the discount is applied twice
+#### when a coupon is reused
+
+The coupon is applied in `add_item` and again in `checkout`.
+
+src/cart.py
+```python
+<<<<<<< SEARCH
+=======
+>>>>>>> REPLACE
+```
+
+> Applied edit to src/cart.py
+
+# aider chat started at 2026-03-02 09:30:00
+
+#### /ask why does checkout round down?
+
+It uses `int()` rather than `round()`.
+";
+
+    #[test]
+    fn each_run_in_the_log_is_its_own_session() {
+        let sections = sections(LOG, "shop");
+        let ids: Vec<&str> = sections.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["20260301-100000@shop", "20260302-093000@shop"]);
+        assert_eq!(sections.iter().map(|s| s.len).sum::<usize>(), LOG.len());
+        assert_eq!(
+            model_in(sections[0].body).as_deref(),
+            Some("anthropic/claude-sonnet-4")
+        );
+    }
+
+    #[test]
+    fn prompts_and_replies_are_kept_and_aider_notices_dropped() {
+        let messages = messages_in(sections(LOG, "shop")[0].body);
+        assert_eq!(messages.len(), 2, "the /add and the notices are not turns");
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(
+            messages[0].text,
+            "the discount is applied twice\nwhen a coupon is reused"
+        );
+        assert_eq!(messages[1].role, Role::Assistant);
+        assert!(messages[1].text.starts_with("The coupon is applied"));
+        assert!(messages[1].text.contains("REPLACE"));
+    }
+
+    #[test]
+    fn a_question_asked_with_a_command_keeps_the_question() {
+        let messages = messages_in(sections(LOG, "shop")[1].body);
+        assert_eq!(messages[0].text, "why does checkout round down?");
+        assert_eq!(prompt_text("/drop src/cart.py"), None);
+        assert_eq!(prompt_text("<blank>"), None);
+    }
+
+    #[test]
+    fn finds_logs_under_the_roots_and_reads_one_run_back() {
+        let root = std::env::temp_dir().join(format!("recall-aider-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let repo = root.join("work").join("shop");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(root.join("node_modules").join("x")).unwrap();
+        fs::write(repo.join(HISTORY_FILE), LOG).unwrap();
+        fs::write(root.join("node_modules").join("x").join(HISTORY_FILE), LOG).unwrap();
+        fs::write(
+            repo.join(INPUT_HISTORY_FILE),
+            "\n# 2026-03-01 10:00:05.123456\n+/add src/cart.py\n\n# 2026-03-01 10:04:00.000000\n+the discount\n",
+        )
+        .unwrap();
+
+        let source = AiderSource {
+            roots: vec![root.clone()],
+        };
+        let sessions = source.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 2, "node_modules is not searched");
+        let first = &sessions[0];
+        assert_eq!(first.uid, "aider:20260301-100000@shop");
+        assert_eq!(first.project, repo.to_string_lossy());
+        assert_eq!(first.last_activity - first.started_at, 4 * 60_000);
+        assert_eq!(
+            first.file_mtime, first.started_at,
+            "finished runs keep their fingerprint"
+        );
+
+        let second = source.load_conversation(&sessions[1]).unwrap();
+        assert_eq!(second.messages.len(), 2);
+        assert_eq!(
+            second.messages[1].text,
+            "It uses `int()` rather than `round()`."
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+//! Conversations imported from ChatGPT and Claude.ai data exports.
+//!
+//! `recall import chat-export` copies each conversation to its own file under
+//! `~/.recall/imports/`, one JSON object per line: a header first, then one
+//! line per message.
+//!
+//! ```json
+//! {"site": "chatgpt.com", "id": "…", "title": "…", "created_at": 1772359200000, "updated_at": 1772359500000, "model": "gpt-4o"}
+//! {"role": "user", "text": "…", "timestamp": 1772359200000, "tool_names": []}
+//! ```
+//!
+//! Listing reads the header only. Nothing here is ever written back to the
+//! service it came from, so these sessions can be read but not resumed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::ai::models::{session_uid, AiSession, Message, Source};
+use crate::ai::sources::{file_mtime, role_of, Conversation, SessionSource};
+
+/// The first line of an imported conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    /// Where the conversation was held, which stands in for its project.
+    pub site: String,
+    pub id: String,
+    pub title: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub model: Option<String>,
+}
+
+/// Every line after the header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Line {
+    pub role: String,
+    pub text: String,
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub tool_names: Vec<String>,
+}
+
+pub struct ChatExportSource {
+    pub(crate) dir: PathBuf,
+}
+
+impl ChatExportSource {
+    pub fn new() -> Self {
+        Self {
+            dir: crate::ai::imports_dir(),
+        }
+    }
+
+    fn read_session(&self, path: &Path) -> Result<AiSession> {
+        let metadata = fs::metadata(path)?;
+        let mut first = String::new();
+        BufReader::new(fs::File::open(path)?).read_line(&mut first)?;
+        let header: Header = serde_json::from_str(&first).context("unreadable header")?;
+
+        let mtime = file_mtime(&metadata);
+
+        Ok(AiSession {
+            uid: session_uid(Source::Chat, &header.id),
+            source: Source::Chat,
+            project: header.site,
+            title: header.title,
+            custom_name: None,
+            started_at: header.created_at,
+            last_activity: header.updated_at.max(header.created_at),
+            model: header.model,
+            message_count: 0,
+            file_path: path.to_string_lossy().to_string(),
+            file_mtime: mtime,
+            file_size: metadata.len() as i64,
+            session_id: header.id,
+        })
+    }
+}
+
+impl SessionSource for ChatExportSource {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        let mut sessions = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(sessions);
+        }
+
+        for entry in walkdir::WalkDir::new(&self.dir)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|e| e != "jsonl") {
+                continue;
+            }
+            if let Ok(session) = self.read_session(path) {
+                sessions.push(session);
+            }
+        }
+        Ok(sessions)
+    }
+
+    fn load_conversation(&self, session: &AiSession) -> Result<Conversation> {
+        let file = fs::File::open(&session.file_path)
+            .with_context(|| format!("Failed to open {}", session.file_path))?;
+        let mut messages = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate().skip(1) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: Line = serde_json::from_str(&line)
+                .with_context(|| format!("line {} of {}", i + 1, session.file_path))?;
+            let Some(role) = role_of(&line.role) else {
+                continue;
+            };
+            messages.push(Message {
+                role,
+                text: line.text,
+                timestamp: line.timestamp,
+                tool_names: line.tool_names,
+            });
+        }
+
+        // Both services title conversations themselves, and let the user
+        // rename them; an export does not say which happened.
+        Ok(Conversation {
+            messages,
+            custom_name: None,
+            generated_title: session.title.clone(),
+        })
+    }
+}
@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::ai::models::{session_uid, AiSession, Message, Role, Source};
-use crate::ai::sources::{Conversation, SessionSource};
+use crate::ai::sources::{file_mtime, Conversation, SessionSource};
 use crate::ai::{parse_rfc3339_millis, projects_dir_claude};
 
 pub struct ClaudeCodeSource {
@@ -229,12 +229,7 @@ impl SessionSource for ClaudeCodeSource {
                 .to_string();
 
             let metadata = fs::metadata(&file_path)?;
-            let file_mtime = metadata
-                .modified()
-                .ok()
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_millis() as i64)
-                .unwrap_or(0);
+            let file_mtime = file_mtime(&metadata);
 
             let file = fs::File::open(&file_path)?;
             let mut started_at = None;
@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::ai::models::{session_uid, AiSession, Message, Role, Source};
-use crate::ai::sources::{Conversation, SessionSource};
+use crate::ai::sources::{file_mtime, Conversation, SessionSource};
 use crate::ai::{parse_rfc3339_millis, sessions_dir_codex};
 
 pub struct CodexSource {
@@ -144,11 +144,9 @@ impl SessionSource for CodexSource {
                                 event.timestamp.as_deref().and_then(parse_rfc3339_millis);
                         }
                     }
-                    "turn_context" => {
-                        if model.is_none() {
-                            if let Ok(ctx) = serde_json::from_value::<TurnContext>(event.payload) {
-                                model = ctx.model;
-                            }
+                    "turn_context" if model.is_none() => {
+                        if let Ok(ctx) = serde_json::from_value::<TurnContext>(event.payload) {
+                            model = ctx.model;
                         }
                     }
                     "response_item" => {
@@ -183,12 +181,7 @@ impl SessionSource for CodexSource {
             });
 
             let metadata = fs::metadata(&file_path)?;
-            let file_mtime = metadata
-                .modified()
-                .ok()
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_millis() as i64)
-                .unwrap_or(0);
+            let file_mtime = file_mtime(&metadata);
 
             let started_at = started_at.unwrap_or(file_mtime);
 
@@ -0,0 +1,470 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ai::models::{session_uid, AiSession, Message, Role, Source};
+use crate::ai::sources::{file_mtime, role_of, text_of, Conversation, SessionSource};
+use crate::ai::{parse_rfc3339_millis, sessions_dir_gemini};
+
+/// Gemini CLI keeps one directory per project under `~/.gemini/tmp/`, named
+/// by a hash of the project path. Inside it, `chats/session-*.json` records
+/// each conversation, and `checkpoint-<tag>.json` holds a chat the user saved
+/// with `/chat save <tag>`.
+pub struct GeminiSource {
+    tmp_dir: PathBuf,
+}
+
+impl GeminiSource {
+    pub fn new() -> Self {
+        Self {
+            tmp_dir: sessions_dir_gemini(),
+        }
+    }
+}
+
+/// A recorded conversation, from `chats/`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatRecord {
+    session_id: Option<String>,
+    start_time: Option<String>,
+    last_updated: Option<String>,
+    /// A title Gemini CLI generated, in versions that write one.
+    summary: Option<String>,
+    #[serde(default)]
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatMessage {
+    #[serde(rename = "type")]
+    message_type: Option<String>,
+    timestamp: Option<String>,
+    /// A string, or a list of parts in some versions.
+    content: Option<serde_json::Value>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+    model: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: Option<String>,
+}
+
+/// One turn of a saved checkpoint, in the Gemini API's own shape.
+#[derive(Deserialize)]
+struct CheckpointTurn {
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    text: Option<String>,
+    function_call: Option<FunctionCall>,
+    /// Set on model reasoning, which is not part of the conversation.
+    #[serde(default)]
+    thought: bool,
+}
+
+#[derive(Deserialize)]
+struct FunctionCall {
+    name: Option<String>,
+}
+
+/// Gemini CLI opens every chat by telling the model about the machine. It is
+/// the same in every session, so it is dropped, along with the reply.
+const SETUP_PREFIX: &str = "This is the Gemini CLI. We are setting up the context for our chat.";
+const SETUP_REPLY: &str = "Got it. Thanks for the context!";
+/// The line of the setup turn that names the project directory.
+const CWD_MARKER: &str = "I'm currently working in the directory: ";
+
+fn is_injected_context(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with(SETUP_PREFIX) || text.starts_with(SETUP_REPLY)
+}
+
+/// The project a hash directory belongs to. Newer versions of Gemini CLI
+/// write it to `.project_root`; otherwise a checkpoint's setup turn names it.
+fn project_for(hash_dir: &Path) -> Option<String> {
+    if let Ok(root) = fs::read_to_string(hash_dir.join(".project_root")) {
+        let root = root.trim();
+        if !root.is_empty() {
+            return Some(root.to_string());
+        }
+    }
+    checkpoint_files(hash_dir)
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok().and_then(|text| cwd_in(&text)))
+}
+
+/// The directory named by a setup turn, read straight from the JSON text so
+/// a large checkpoint need not be parsed to find it.
+fn cwd_in(text: &str) -> Option<String> {
+    let start = text.find(CWD_MARKER)? + CWD_MARKER.len();
+    let rest = &text[start..];
+    let end = rest.find(['\\', '"', '\n']).unwrap_or(rest.len());
+    let cwd = rest[..end].trim();
+    (!cwd.is_empty()).then(|| cwd.to_string())
+}
+
+fn checkpoint_files(hash_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(hash_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| checkpoint_tag(p).is_some())
+        .collect()
+}
+
+fn chat_files(hash_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(hash_dir.join("chats")) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension().is_some_and(|e| e == "json")
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("session-"))
+        })
+        .collect()
+}
+
+/// The tag a checkpoint was saved under, from `checkpoint-<tag>.json`.
+pub fn checkpoint_tag(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let tag = name.strip_prefix("checkpoint-")?.strip_suffix(".json")?;
+    (!tag.is_empty()).then(|| percent_decode(tag))
+}
+
+/// Tags are written URL-encoded, so `my plan` is `my%20plan` on disk.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|pair| std::str::from_utf8(pair).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn chat_messages(record: &ChatRecord) -> Vec<Message> {
+    let mut messages = Vec::new();
+    for message in &record.messages {
+        // `info`, `error` and `warning` are the CLI talking, not the chat.
+        let Some(role) = message.message_type.as_deref().and_then(role_of) else {
+            continue;
+        };
+        let text = message.content.as_ref().map(text_of).unwrap_or_default();
+        if text.trim().is_empty() || is_injected_context(&text) {
+            continue;
+        }
+        messages.push(Message {
+            role,
+            text,
+            timestamp: message.timestamp.as_deref().and_then(parse_rfc3339_millis),
+            tool_names: message
+                .tool_calls
+                .iter()
+                .filter_map(|call| call.name.clone())
+                .collect(),
+        });
+    }
+    messages
+}
+
+fn checkpoint_messages(turns: &[CheckpointTurn]) -> Vec<Message> {
+    let mut messages = Vec::new();
+    for turn in turns {
+        let Some(role) = turn.role.as_deref().and_then(role_of) else {
+            continue;
+        };
+        let text = turn
+            .parts
+            .iter()
+            .filter(|part| !part.thought)
+            .filter_map(|part| part.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n");
+        // A user turn of only function responses is tool output, not a prompt.
+        if text.trim().is_empty() || is_injected_context(&text) {
+            continue;
+        }
+        messages.push(Message {
+            role,
+            text,
+            timestamp: None,
+            tool_names: turn
+                .parts
+                .iter()
+                .filter_map(|part| part.function_call.as_ref()?.name.clone())
+                .collect(),
+        });
+    }
+    messages
+}
+
+fn opening_prompt(messages: &[Message]) -> Option<String> {
+    messages
+        .iter()
+        .find(|m| m.role == Role::User)
+        .map(|m| m.text.trim().chars().take(120).collect())
+}
+
+impl GeminiSource {
+    fn list_chat(&self, path: &Path, project: &str) -> Result<Option<AiSession>> {
+        let metadata = fs::metadata(path)?;
+        let mtime = file_mtime(&metadata);
+        let text = fs::read_to_string(path)?;
+        let Ok(record) = serde_json::from_str::<ChatRecord>(&text) else {
+            return Ok(None);
+        };
+        let session_id = record.session_id.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        });
+        let started_at = record
+            .start_time
+            .as_deref()
+            .and_then(parse_rfc3339_millis)
+            .unwrap_or(mtime);
+        let updated = record
+            .last_updated
+            .as_deref()
+            .and_then(parse_rfc3339_millis);
+
+        Ok(Some(AiSession {
+            uid: session_uid(Source::Gemini, &session_id),
+            source: Source::Gemini,
+            session_id,
+            project: project.to_string(),
+            title: record
+                .summary
+                .clone()
+                .or_else(|| opening_prompt(&chat_messages(&record))),
+            custom_name: None,
+            started_at,
+            last_activity: updated.unwrap_or(mtime).max(started_at),
+            model: record.messages.iter().find_map(|m| m.model.clone()),
+            message_count: 0,
+            file_path: path.to_string_lossy().to_string(),
+            file_mtime: mtime,
+            file_size: metadata.len() as i64,
+        }))
+    }
+
+    fn list_checkpoint(&self, path: &Path, hash: &str, project: &str) -> Result<Option<AiSession>> {
+        let Some(tag) = checkpoint_tag(path) else {
+            return Ok(None);
+        };
+        let metadata = fs::metadata(path)?;
+        let mtime = file_mtime(&metadata);
+        // Tags are only unique within a project.
+        let session_id = format!("{}@{}", tag, hash.chars().take(8).collect::<String>());
+
+        Ok(Some(AiSession {
+            uid: session_uid(Source::Gemini, &session_id),
+            source: Source::Gemini,
+            session_id,
+            project: project.to_string(),
+            title: Some(tag.clone()),
+            custom_name: Some(tag),
+            // Checkpoints record no times; the save is all there is.
+            started_at: mtime,
+            last_activity: mtime,
+            model: None,
+            message_count: 0,
+            file_path: path.to_string_lossy().to_string(),
+            file_mtime: mtime,
+            file_size: metadata.len() as i64,
+        }))
+    }
+}
+
+impl SessionSource for GeminiSource {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        let Ok(entries) = fs::read_dir(&self.tmp_dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut sessions = Vec::new();
+        for hash_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if !hash_dir.is_dir() {
+                continue;
+            }
+            let hash = hash_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let project = project_for(&hash_dir).unwrap_or_else(|| "unknown".to_string());
+
+            for path in chat_files(&hash_dir) {
+                sessions.extend(self.list_chat(&path, &project)?);
+            }
+            for path in checkpoint_files(&hash_dir) {
+                sessions.extend(self.list_checkpoint(&path, &hash, &project)?);
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    fn load_conversation(&self, session: &AiSession) -> Result<Conversation> {
+        let text = fs::read_to_string(&session.file_path)
+            .with_context(|| format!("Failed to open {}", session.file_path))?;
+
+        if checkpoint_tag(Path::new(&session.file_path)).is_some() {
+            let turns: Vec<CheckpointTurn> = serde_json::from_str(&text)
+                .with_context(|| format!("Failed to parse {}", session.file_path))?;
+            return Ok(Conversation {
+                messages: checkpoint_messages(&turns),
+                custom_name: session.custom_name.clone(),
+                generated_title: None,
+            });
+        }
+
+        let record: ChatRecord = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", session.file_path))?;
+        Ok(Conversation {
+            messages: chat_messages(&record),
+            custom_name: None,
+            generated_title: record.summary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAT: &str = r#"{
+        "sessionId": "6f1c2a9e-0000-4000-8000-000000000001",
+        "projectHash": "ab12cd34ef",
+        "startTime": "2026-03-01T10:00:00.000Z",
+        "lastUpdated": "2026-03-01T10:05:00.000Z",
+        "messages": [
+            {"type": "user", "timestamp": "2026-03-01T10:00:00.000Z", "content": "why is the build slow?"},
+            {"type": "gemini", "timestamp": "2026-03-01T10:00:09.000Z", "content": "Let me look at the build script.",
+             "toolCalls": [{"id": "t1", "name": "read_file", "args": {"path": "build.rs"}}],
+             "thoughts": [{"subject": "Planning", "description": "..."}],
+             "model": "gemini-2.5-pro"},
+            {"type": "info", "content": "Request cancelled."}
+        ]
+    }"#;
+
+    const CHECKPOINT: &str = r#"[
+        {"role": "user", "parts": [{"text": "This is the Gemini CLI. We are setting up the context for our chat.\nToday's date is Sunday.\nI'm currently working in the directory: /repos/shop\nHere is the folder structure"}]},
+        {"role": "model", "parts": [{"text": "Got it. Thanks for the context!"}]},
+        {"role": "user", "parts": [{"text": "plan the checkout refactor"}]},
+        {"role": "model", "parts": [{"text": "thinking", "thought": true}, {"text": "Here is a plan."}, {"functionCall": {"name": "glob", "args": {}}}]},
+        {"role": "user", "parts": [{"functionResponse": {"name": "glob", "response": {}}}]}
+    ]"#;
+
+    fn fixture(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("recall-gemini-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let hash_dir = root.join("ab12cd34ef");
+        fs::create_dir_all(hash_dir.join("chats")).unwrap();
+        fs::write(
+            hash_dir
+                .join("chats")
+                .join("session-2026-03-01T10-00-6f1c2a9e.json"),
+            CHAT,
+        )
+        .unwrap();
+        fs::write(hash_dir.join("checkpoint-checkout%20plan.json"), CHECKPOINT).unwrap();
+        fs::write(hash_dir.join("logs.json"), "[]").unwrap();
+        root
+    }
+
+    #[test]
+    fn lists_chats_and_saved_checkpoints_under_their_project() {
+        let root = fixture("list");
+        let source = GeminiSource {
+            tmp_dir: root.clone(),
+        };
+        let mut sessions = source.list_sessions().unwrap();
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions.len(), 2);
+
+        let chat = &sessions[0];
+        assert_eq!(chat.uid, "gemini:6f1c2a9e-0000-4000-8000-000000000001");
+        assert_eq!(chat.title.as_deref(), Some("why is the build slow?"));
+        assert_eq!(chat.model.as_deref(), Some("gemini-2.5-pro"));
+        assert_eq!(chat.last_activity - chat.started_at, 5 * 60_000);
+        // The directory is a hash; a checkpoint's setup turn names it.
+        assert_eq!(chat.project, "/repos/shop");
+
+        let saved = &sessions[1];
+        assert_eq!(saved.session_id, "checkout plan@ab12cd34");
+        assert_eq!(saved.custom_name.as_deref(), Some("checkout plan"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn a_project_root_file_names_the_project() {
+        let root = fixture("root");
+        fs::write(
+            root.join("ab12cd34ef").join(".project_root"),
+            "/repos/elsewhere\n",
+        )
+        .unwrap();
+        assert_eq!(
+            project_for(&root.join("ab12cd34ef")).as_deref(),
+            Some("/repos/elsewhere")
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn chat_turns_keep_text_and_tool_calls_and_drop_cli_notices() {
+        let record: ChatRecord = serde_json::from_str(CHAT).unwrap();
+        let messages = chat_messages(&record);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(messages[1].role, Role::Assistant);
+        assert_eq!(messages[1].tool_names, vec!["read_file"]);
+        assert!(messages[1].timestamp.is_some());
+    }
+
+    #[test]
+    fn checkpoints_drop_the_setup_turns_thoughts_and_tool_output() {
+        let turns: Vec<CheckpointTurn> = serde_json::from_str(CHECKPOINT).unwrap();
+        let messages = checkpoint_messages(&turns);
+        let texts: Vec<&str> = messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["plan the checkout refactor", "Here is a plan."]);
+        assert_eq!(messages[1].tool_names, vec!["glob"]);
+    }
+
+    #[test]
+    fn content_may_be_a_list_of_parts() {
+        let parts = serde_json::json!([{"text": "one"}, {"text": "hidden", "thought": true}, {"text": "two"}]);
+        assert_eq!(text_of(&parts), "one\ntwo");
+    }
+}
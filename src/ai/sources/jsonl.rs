@@ -0,0 +1,330 @@
+//! Transcript sources described in config.toml rather than in code. Each
+//! `[[sources]]` entry names a glob of JSONL files and the JSON pointers that
+//! say where a line keeps its session id, directory, time, speaker and text.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::ai::models::{session_uid, AiSession, Message, Source};
+use crate::ai::sources::{file_mtime, role_of, text_of, time_of, Conversation, SessionSource};
+use crate::config::settings::SourceConfig;
+
+/// Lines read when listing, to find a session's id, directory and start.
+const HEADER_LINES: usize = 50;
+
+pub struct GenericJsonlSource {
+    source: Source,
+    config: SourceConfig,
+}
+
+impl GenericJsonlSource {
+    pub fn new(source: Source, config: SourceConfig) -> Self {
+        Self { source, config }
+    }
+
+    fn read_session(&self, path: &Path) -> Result<AiSession> {
+        let metadata = fs::metadata(path)?;
+        let mtime = file_mtime(&metadata);
+
+        let mut session_id = None;
+        let mut cwd = None;
+        let mut started_at = None;
+        let file = fs::File::open(path)?;
+        for line in BufReader::new(file).lines().take(HEADER_LINES) {
+            let Ok(event) = serde_json::from_str::<Value>(&line?) else {
+                continue;
+            };
+            session_id =
+                session_id.or_else(|| string_at(&event, self.config.session_id.as_deref()));
+            cwd = cwd.or_else(|| string_at(&event, self.config.cwd.as_deref()));
+            started_at = started_at.or_else(|| time_at(&event, self.config.timestamp.as_deref()));
+            if session_id.is_some() && cwd.is_some() && started_at.is_some() {
+                break;
+            }
+        }
+
+        let session_id = session_id.unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        });
+        let source = self.source;
+        let started_at = started_at.unwrap_or(mtime);
+
+        Ok(AiSession {
+            uid: session_uid(source, &session_id),
+            source,
+            session_id,
+            project: cwd.unwrap_or_else(|| "unknown".to_string()),
+            title: None,
+            custom_name: None,
+            started_at,
+            last_activity: mtime.max(started_at),
+            model: None,
+            message_count: 0,
+            file_path: path.to_string_lossy().to_string(),
+            file_mtime: mtime,
+            file_size: metadata.len() as i64,
+        })
+    }
+
+    /// One line as a message, when it is a kept type with a speaker and text.
+    fn message(&self, event: &Value) -> Option<Message> {
+        if !self.config.keep.is_empty() {
+            let kind = string_at(event, self.config.event_type.as_deref())?;
+            if !self.config.keep.contains(&kind) {
+                return None;
+            }
+        }
+        let role = role_of(&string_at(event, self.config.role.as_deref())?)?;
+        let text = text_of(at(event, self.config.text.as_deref()?)?);
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(Message {
+            role,
+            text,
+            timestamp: time_at(event, self.config.timestamp.as_deref()),
+            tool_names: Vec::new(),
+        })
+    }
+}
+
+/// The value at a JSON pointer. A missing leading `/` is forgiven.
+fn at<'a>(event: &'a Value, pointer: &str) -> Option<&'a Value> {
+    if pointer.starts_with('/') || pointer.is_empty() {
+        event.pointer(pointer)
+    } else {
+        event.pointer(&format!("/{}", pointer))
+    }
+}
+
+fn string_at(event: &Value, pointer: Option<&str>) -> Option<String> {
+    match at(event, pointer?)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn time_at(event: &Value, pointer: Option<&str>) -> Option<i64> {
+    time_of(at(event, pointer?)?)
+}
+
+/// Files matching `pattern`, where `*` and `?` stay within one directory and
+/// `**` crosses any number of them. A leading `~` is the home directory.
+fn glob_files(pattern: &str) -> Vec<PathBuf> {
+    let pattern = match pattern.strip_prefix('~') {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        None => PathBuf::from(pattern),
+    };
+
+    // Walk from the deepest directory that has no wildcard in it.
+    let mut base = PathBuf::new();
+    let mut rest: Vec<String> = Vec::new();
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy().to_string();
+        let wild = part.contains(['*', '?']);
+        if rest.is_empty() && !wild {
+            base.push(component);
+        } else if !matches!(component, Component::CurDir) {
+            rest.push(part);
+        }
+    }
+    if rest.is_empty() {
+        return if base.is_file() {
+            vec![base]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let mut walk = WalkDir::new(&base).min_depth(1);
+    if !rest.iter().any(|part| part == "**") {
+        walk = walk.max_depth(rest.len());
+    }
+    let mut files: Vec<PathBuf> = walk
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let relative: Vec<String> = e
+                .path()
+                .strip_prefix(&base)
+                .map(|p| {
+                    p.components()
+                        .map(|c| c.as_os_str().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            path_matches(&rest, &relative)
+        })
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn path_matches(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| path_matches(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((name, path)) => {
+                name_matches(first.as_bytes(), name.as_bytes()) && path_matches(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn name_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| name_matches(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && name_matches(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && name_matches(rest, &name[1..]),
+    }
+}
+
+impl SessionSource for GenericJsonlSource {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        let mut sessions = Vec::new();
+        let Some(glob) = &self.config.glob else {
+            return Ok(sessions);
+        };
+        for path in glob_files(glob) {
+            sessions.push(self.read_session(&path)?);
+        }
+        Ok(sessions)
+    }
+
+    fn load_conversation(&self, session: &AiSession) -> Result<Conversation> {
+        let file = fs::File::open(&session.file_path)
+            .with_context(|| format!("Failed to open {}", session.file_path))?;
+        let mut messages = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let Ok(event) = serde_json::from_str::<Value>(&line?) else {
+                continue;
+            };
+            messages.extend(self.message(&event));
+        }
+        Ok(Conversation {
+            messages,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::Role;
+
+    fn config(glob: &str) -> SourceConfig {
+        toml::from_str(&format!(
+            r#"
+            name = "acme"
+            glob = "{glob}"
+            session_id = "/session"
+            cwd = "/env/cwd"
+            timestamp = "/ts"
+            role = "/msg/role"
+            text = "/msg/content"
+            event_type = "/kind"
+            keep = ["message"]
+            "#
+        ))
+        .unwrap()
+    }
+
+    const TRANSCRIPT: &str = r#"{"kind":"start","session":"run-42","env":{"cwd":"/repos/billing"},"ts":1772359200}
+{"kind":"message","ts":"2026-03-01T10:00:05Z","msg":{"role":"user","content":"why are invoices doubled?"}}
+{"kind":"trace","msg":{"role":"assistant","content":"internal reasoning"}}
+{"kind":"message","ts":"2026-03-01T10:00:30Z","msg":{"role":"assistant","content":[{"type":"text","text":"The webhook retries."}]}}
+not json at all
+"#;
+
+    fn fixture() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("recall-jsonl-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("2026").join("03")).unwrap();
+        fs::write(
+            root.join("2026").join("03").join("run-42.jsonl"),
+            TRANSCRIPT,
+        )
+        .unwrap();
+        fs::write(root.join("2026").join("03").join("notes.txt"), "ignored").unwrap();
+        root
+    }
+
+    #[test]
+    fn globs_match_within_and_across_directories() {
+        let parts = |s: &str| s.split('/').map(str::to_string).collect::<Vec<_>>();
+        assert!(path_matches(
+            &parts("**/*.jsonl"),
+            &parts("2026/03/a.jsonl")
+        ));
+        assert!(path_matches(&parts("**/*.jsonl"), &parts("a.jsonl")));
+        assert!(path_matches(
+            &parts("*/run-??.jsonl"),
+            &parts("03/run-42.jsonl")
+        ));
+        assert!(!path_matches(&parts("*.jsonl"), &parts("03/a.jsonl")));
+        assert!(!path_matches(&parts("*.jsonl"), &parts("a.json")));
+    }
+
+    #[test]
+    fn a_configured_source_lists_and_reads_its_transcripts() {
+        let root = fixture();
+        let source = GenericJsonlSource::new(
+            Source::Custom("acme"),
+            config(&format!("{}/**/*.jsonl", root.display())),
+        );
+        let sessions = source.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.uid, "acme:run-42");
+        assert_eq!(session.project, "/repos/billing");
+        assert_eq!(session.started_at, 1_772_359_200_000, "epoch seconds");
+
+        let conversation = source.load_conversation(session).unwrap();
+        let texts: Vec<&str> = conversation
+            .messages
+            .iter()
+            .map(|m| m.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["why are invoices doubled?", "The webhook retries."]
+        );
+        assert_eq!(conversation.messages[1].role, Role::Assistant);
+        assert!(conversation.messages[0].timestamp.is_some());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn without_a_session_id_the_file_name_is_the_id() {
+        let root = fixture();
+        let mut config = config(&format!("{}/*/*/*.jsonl", root.display()));
+        config.session_id = None;
+        let sessions = GenericJsonlSource::new(Source::Custom("acme"), config)
+            .list_sessions()
+            .unwrap();
+        assert_eq!(sessions[0].session_id, "run-42");
+        assert_eq!(
+            sessions[0].file_path,
+            root.join("2026/03/run-42.jsonl").to_string_lossy()
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
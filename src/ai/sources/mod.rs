@@ -1,9 +1,19 @@
+pub mod aider;
+pub mod chat_export;
 pub mod claude_code;
 pub mod codex;
+pub mod gemini;
+pub mod jsonl;
+pub mod plugin;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::fs;
+use std::sync::OnceLock;
 
-use super::models::{AiSession, Message, Source};
+use super::models::{AiSession, Message, Role, Source};
+use super::parse_rfc3339_millis;
+use crate::config::settings::{load_config, SourceConfig};
 
 /// Everything a full parse of one transcript yields.
 #[derive(Debug, Clone, Default)]
@@ -24,9 +34,128 @@ pub trait SessionSource {
     fn load_conversation(&self, session: &AiSession) -> Result<Conversation>;
 }
 
+/// A file's modification time in epoch milliseconds, or 0 when the platform
+/// cannot say.
+pub(crate) fn file_mtime(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// A speaker as recall sees it. Anything else — `system`, `tool`, a CLI's
+/// own `info` lines — is not part of the conversation.
+pub(crate) fn role_of(name: &str) -> Option<Role> {
+    match name.to_ascii_lowercase().as_str() {
+        "user" | "human" => Some(Role::User),
+        "assistant" | "model" | "gemini" | "ai" | "agent" | "bot" => Some(Role::Assistant),
+        _ => None,
+    }
+}
+
+/// RFC 3339, or a number of seconds — or milliseconds, when it is too big to
+/// be seconds.
+pub(crate) fn time_of(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(s) => parse_rfc3339_millis(s),
+        Value::Number(n) => {
+            let n = n.as_f64()?;
+            Some(if n < 1e11 {
+                (n * 1000.0) as i64
+            } else {
+                n as i64
+            })
+        }
+        _ => None,
+    }
+}
+
+/// A message's text, whether a string or a list of parts. Parts a model
+/// marks as its own thinking are left out.
+pub(crate) fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter(|part| {
+                !part
+                    .get("thought")
+                    .and_then(|t| t.as_bool())
+                    .unwrap_or(false)
+            })
+            .filter_map(|part| match part {
+                Value::String(s) => Some(s.as_str()),
+                _ => part.get("text").and_then(|t| t.as_str()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// The `[[sources]]` in use, read from config once per run. An entry is
+/// ignored when its name is taken — by a built-in source, by `shell`, or by
+/// an earlier entry — or holds anything but letters, digits, `-` and `_`,
+/// and when it has neither a `glob` nor a `command` to read sessions from.
+pub fn configured() -> &'static [SourceConfig] {
+    static CONFIGURED: OnceLock<Vec<SourceConfig>> = OnceLock::new();
+    CONFIGURED.get_or_init(|| {
+        let sources = load_config()
+            .map(|config| config.sources)
+            .unwrap_or_default();
+        let mut usable: Vec<SourceConfig> = Vec::new();
+        for source in sources {
+            let name = source.name.as_str();
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            let taken = Source::parse_builtin(name).is_some()
+                || name.eq_ignore_ascii_case("shell")
+                || usable.iter().any(|s| s.name.eq_ignore_ascii_case(name));
+            let readable = source.command.is_some() || source.glob.is_some();
+            if valid && !taken && readable {
+                usable.push(source);
+            }
+        }
+        usable
+    })
+}
+
+/// The configured source called `name`.
+pub fn config_for(name: &str) -> Option<&'static SourceConfig> {
+    configured().iter().find(|s| s.name == name)
+}
+
 pub fn source_for(source: Source) -> Box<dyn SessionSource> {
     match source {
         Source::Claude => Box::new(claude_code::ClaudeCodeSource::new()),
         Source::Codex => Box::new(codex::CodexSource::new()),
+        Source::Gemini => Box::new(gemini::GeminiSource::new()),
+        Source::Aider => Box::new(aider::AiderSource::new()),
+        Source::Chat => Box::new(chat_export::ChatExportSource::new()),
+        Source::Custom(name) => match config_for(name) {
+            Some(config) if config.command.is_some() => {
+                Box::new(plugin::PluginSource::new(source, config.clone()))
+            }
+            Some(config) => Box::new(jsonl::GenericJsonlSource::new(source, config.clone())),
+            None => Box::new(Unconfigured(name)),
+        },
+    }
+}
+
+/// A source whose `[[sources]]` entry has gone. It has nothing on disk, so
+/// its sessions drop out of the index on the next run.
+struct Unconfigured(&'static str);
+
+impl SessionSource for Unconfigured {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        Ok(Vec::new())
+    }
+
+    fn load_conversation(&self, _session: &AiSession) -> Result<Conversation> {
+        bail!("`{}` is no longer configured in [[sources]]", self.0)
     }
 }
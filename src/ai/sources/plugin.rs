@@ -0,0 +1,342 @@
+//! Sources read by running a program rather than by reading files, for tools
+//! that keep conversations somewhere recall cannot parse itself.
+//!
+//! The program is run as `<command> list`, printing one session per line:
+//!
+//! ```json
+//! {"session_id": "a1", "project": "/repos/app", "title": "…", "custom_name": null,
+//!  "started_at": 1772359200000, "last_activity": "2026-03-01T10:05:00Z",
+//!  "model": "…", "file_path": "…", "file_size": 4096}
+//! ```
+//!
+//! and as `<command> load <session_id>`, printing one message per line:
+//!
+//! ```json
+//! {"role": "user", "text": "…", "timestamp": 1772359205000, "tool_names": []}
+//! ```
+//!
+//! Only `session_id`, `role` and `text` are required. Times are milliseconds,
+//! seconds or RFC 3339; `text` may be a list of parts with `text`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ai::models::{session_uid, AiSession, Message, Source};
+use crate::ai::sources::{role_of, text_of, time_of, Conversation, SessionSource};
+use crate::config::settings::SourceConfig;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+pub struct PluginSource {
+    source: Source,
+    command: String,
+    timeout: Duration,
+}
+
+impl PluginSource {
+    pub fn new(source: Source, config: SourceConfig) -> Self {
+        Self {
+            source,
+            command: config.command.unwrap_or_default(),
+            timeout: Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        }
+    }
+
+    /// Run the program with `args` and return what it printed. One that runs
+    /// past the timeout is killed; one that fails is reported with the last
+    /// thing it wrote to stderr.
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let mut words = self.command.split_whitespace();
+        let Some(program) = words.next() else {
+            bail!("the command is empty");
+        };
+        let program = match program.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => program.into(),
+        };
+
+        let mut child = Command::new(&program)
+            .args(words)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run `{}`", self.command))?;
+
+        // Drain both pipes while waiting, so a chatty plugin cannot fill one
+        // and stall.
+        let stdout = child.stdout.take().map(drain);
+        let stderr = child.stderr.take().map(drain);
+
+        let deadline = Instant::now() + self.timeout;
+        let timed_out = || {
+            anyhow!(
+                "`{} {}` timed out after {:.1}s",
+                self.command,
+                args.join(" "),
+                self.timeout.as_secs_f64()
+            )
+        };
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        // A helper the plugin left running can hold its pipes open after it
+        // exits, so reading them keeps to the same deadline.
+        let collect = |pipe: Option<mpsc::Receiver<String>>| match pipe {
+            Some(pipe) => pipe
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .map_err(|_| timed_out()),
+            None => Ok(String::new()),
+        };
+        let output = collect(stdout)?;
+        if !status.success() {
+            let errors = collect(stderr)?;
+            let reason = errors
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .unwrap_or("");
+            bail!(
+                "`{} {}` exited with {}{}{}",
+                self.command,
+                args.join(" "),
+                status
+                    .code()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "a signal".into()),
+                if reason.is_empty() { "" } else { ": " },
+                reason.trim()
+            );
+        }
+        Ok(output)
+    }
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        let _ = sender.send(String::from_utf8_lossy(&bytes).into_owned());
+    });
+    receiver
+}
+
+/// One line of `list`.
+#[derive(Deserialize)]
+struct ListedSession {
+    session_id: String,
+    project: Option<String>,
+    title: Option<String>,
+    custom_name: Option<String>,
+    started_at: Option<Value>,
+    last_activity: Option<Value>,
+    model: Option<String>,
+    file_path: Option<String>,
+    file_size: Option<i64>,
+}
+
+/// One line of `load`.
+#[derive(Deserialize)]
+struct LoadedMessage {
+    role: String,
+    text: Value,
+    timestamp: Option<Value>,
+    #[serde(default)]
+    tool_names: Vec<String>,
+}
+
+/// Each non-blank line of `output` as a `T`, or which line was not one.
+fn parse_lines<T: for<'de> Deserialize<'de>>(output: &str) -> Result<Vec<T>> {
+    output
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).with_context(|| format!("line {} of the output", i + 1))
+        })
+        .collect()
+}
+
+impl SessionSource for PluginSource {
+    fn list_sessions(&self) -> Result<Vec<AiSession>> {
+        let listed: Vec<ListedSession> = parse_lines(&self.run(&["list"])?)?;
+        Ok(listed
+            .into_iter()
+            .map(|listed| {
+                let started_at = listed.started_at.as_ref().and_then(time_of).unwrap_or(0);
+                let last_activity = listed
+                    .last_activity
+                    .as_ref()
+                    .and_then(time_of)
+                    .unwrap_or(started_at)
+                    .max(started_at);
+                AiSession {
+                    uid: session_uid(self.source, &listed.session_id),
+                    source: self.source,
+                    project: listed.project.unwrap_or_else(|| "unknown".to_string()),
+                    title: listed.title,
+                    custom_name: listed.custom_name,
+                    started_at,
+                    last_activity,
+                    model: listed.model,
+                    message_count: 0,
+                    file_path: listed.file_path.unwrap_or_else(|| self.command.clone()),
+                    // A session is re-read when it has been active since, or
+                    // when the plugin reports a new size for it.
+                    file_mtime: last_activity,
+                    file_size: listed.file_size.unwrap_or(0),
+                    session_id: listed.session_id,
+                }
+            })
+            .collect())
+    }
+
+    fn load_conversation(&self, session: &AiSession) -> Result<Conversation> {
+        let loaded: Vec<LoadedMessage> = parse_lines(&self.run(&["load", &session.session_id])?)?;
+        let messages = loaded
+            .into_iter()
+            .filter_map(|message| {
+                let role = role_of(&message.role)?;
+                let text = text_of(&message.text);
+                (!text.trim().is_empty()).then(|| Message {
+                    role,
+                    text,
+                    timestamp: message.timestamp.as_ref().and_then(time_of),
+                    tool_names: message.tool_names,
+                })
+            })
+            .collect();
+
+        // The listing is where a plugin names its sessions.
+        Ok(Conversation {
+            messages,
+            custom_name: session.custom_name.clone(),
+            generated_title: session.title.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const PLUGIN: &str = r#"
+case "$1" in
+  list)
+    echo '{"session_id":"p1","project":"/repos/app","custom_name":"design review","started_at":1772359200,"last_activity":"2026-03-01T10:05:00Z"}'
+    echo
+    echo '{"session_id":"p2"}'
+    ;;
+  load)
+    echo '{"role":"user","text":"what did we decide about '"$2"'?"}'
+    echo '{"role":"system","text":"ignored"}'
+    echo '{"role":"assistant","text":[{"text":"Postgres."}],"tool_names":["search"]}'
+    ;;
+  broken)
+    echo 'not json'
+    ;;
+esac
+"#;
+
+    fn script(name: &str, body: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("recall-plugin-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, body).unwrap();
+        path
+    }
+
+    fn plugin(command: String) -> PluginSource {
+        PluginSource {
+            source: Source::Custom("notes"),
+            command,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn lists_sessions_and_loads_their_messages() {
+        let source = plugin(format!("sh {}", script("ok.sh", PLUGIN).display()));
+        let sessions = source.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 2, "blank lines are skipped");
+        let first = &sessions[0];
+        assert_eq!(first.uid, "notes:p1");
+        assert_eq!(first.custom_name.as_deref(), Some("design review"));
+        assert_eq!(first.started_at, 1_772_359_200_000);
+        assert_eq!(first.last_activity - first.started_at, 5 * 60_000);
+        assert_eq!(sessions[1].project, "unknown");
+
+        let conversation = source.load_conversation(first).unwrap();
+        let texts: Vec<&str> = conversation
+            .messages
+            .iter()
+            .map(|m| m.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["what did we decide about p1?", "Postgres."]);
+        assert_eq!(conversation.messages[1].tool_names, vec!["search"]);
+        assert_eq!(conversation.custom_name.as_deref(), Some("design review"));
+    }
+
+    #[test]
+    fn a_slow_plugin_is_stopped_at_its_timeout() {
+        let mut source = plugin(format!("sh {}", script("slow.sh", "sleep 5").display()));
+        source.timeout = Duration::from_millis(100);
+        let started = Instant::now();
+        let error = source.list_sessions().unwrap_err().to_string();
+        assert!(error.contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn a_helper_left_holding_the_output_does_not_hang_the_read() {
+        let mut source = plugin(format!(
+            "sh {}",
+            script("helper.sh", "sleep 5 &\necho '{}'").display()
+        ));
+        source.timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        let error = source.list_sessions().unwrap_err().to_string();
+        assert!(error.contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn failures_say_what_went_wrong() {
+        let failing = plugin(format!(
+            "sh {}",
+            script("fail.sh", "echo 'no database at ~/.notes' >&2; exit 3").display()
+        ));
+        let error = failing.list_sessions().unwrap_err().to_string();
+        assert!(
+            error.contains("exited with 3: no database at ~/.notes"),
+            "{}",
+            error
+        );
+
+        let garbled = plugin(format!("sh {} broken", script("ok.sh", PLUGIN).display()));
+        let error = format!("{:#}", garbled.list_sessions().unwrap_err());
+        assert!(error.contains("line 1 of the output"), "{}", error);
+
+        let missing = plugin("/nonexistent/recall-plugin".to_string());
+        assert!(missing.list_sessions().is_err());
+    }
+}
@@ -5,7 +5,9 @@ use anyhow::{Context, Result};
 use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, Row};
 use std::collections::HashMap;
 
-use super::models::{AiSearchResult, AiSession, Chunk, Source};
+use super::models::{AiSearchResult, AiSession, Chunk, Source, MATCH_END, MATCH_START};
+use crate::config::settings::RankingConfig;
+use crate::search::query::Query;
 
 /// The filters every listing and search shares.
 #[derive(Debug, Clone, Default)]
@@ -13,7 +15,16 @@ pub struct Filter {
     pub source: Option<Source>,
     /// Matched as a substring of the session's project path.
     pub project: Option<String>,
+    /// Epoch milliseconds: sessions still active at or after this time.
+    pub since: Option<i64>,
+    /// Epoch milliseconds: sessions started before this time.
+    pub until: Option<i64>,
+    /// The parsed search box: its text for searches, its `key:value` filters
+    /// and exclusions for listings too.
+    pub query: Query,
     pub limit: usize,
+    /// How searches order what they find; `[search.ranking]` in config.toml.
+    pub ranking: RankingConfig,
 }
 
 impl Filter {
@@ -25,7 +36,7 @@ impl Filter {
     }
 
     /// SQL predicate plus its bound values, for splicing into a WHERE clause.
-    fn clauses(&self, alias: &str) -> (String, Vec<SqlValue>) {
+    pub(crate) fn clauses(&self, alias: &str) -> (String, Vec<SqlValue>) {
         let mut sql = String::new();
         let mut values = Vec::new();
 
@@ -37,6 +48,17 @@ impl Filter {
             sql.push_str(&format!(" AND {}.project LIKE ?", alias));
             values.push(SqlValue::Text(format!("%{}%", project)));
         }
+        if let Some(since) = self.since {
+            sql.push_str(&format!(" AND {}.last_activity >= ?", alias));
+            values.push(SqlValue::Integer(since));
+        }
+        if let Some(until) = self.until {
+            sql.push_str(&format!(" AND {}.started_at < ?", alias));
+            values.push(SqlValue::Integer(until));
+        }
+        let (query_sql, query_values) = self.query.session_clauses(alias);
+        sql.push_str(&query_sql);
+        values.extend(query_values);
 
         (sql, values)
     }
@@ -49,7 +71,7 @@ fn session_from_row(row: &Row, offset: usize) -> rusqlite::Result<AiSession> {
     let source: String = row.get(offset + 1)?;
     Ok(AiSession {
         uid: row.get(offset)?,
-        source: Source::parse(&source).unwrap_or(Source::Claude),
+        source: Source::from_stored(&source),
         session_id: row.get(offset + 2)?,
         project: row.get(offset + 3)?,
         title: row.get(offset + 4)?,
@@ -64,16 +86,6 @@ fn session_from_row(row: &Row, offset: usize) -> rusqlite::Result<AiSession> {
     })
 }
 
-/// Build an FTS5 MATCH expression that treats every token as a literal and
-/// requires all of them, so paths and flags like `--resume` can't be read as
-/// query operators.
-pub fn fts_query(raw: &str) -> String {
-    raw.split_whitespace()
-        .map(|token| format!("\"{}\"", token.replace('"', "\"\"")))
-        .collect::<Vec<_>>()
-        .join(" AND ")
-}
-
 pub fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>> {
     let mut stmt = conn.prepare("SELECT value FROM recall_meta WHERE key = ?1")?;
     let mut rows = stmt.query_map(params![key], |row| row.get::<_, String>(0))?;
@@ -185,6 +197,19 @@ pub fn uids_for_source(conn: &Connection, source: Source) -> Result<Vec<String>>
     Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
 }
 
+/// Sessions from any source other than these — ones whose `[[sources]]`
+/// entry has since been removed.
+pub fn uids_outside(conn: &Connection, sources: &[Source]) -> Result<Vec<String>> {
+    let names: Vec<&str> = sources.iter().map(|source| source.as_str()).collect();
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT uid FROM ai_sessions WHERE source NOT IN ({})",
+        placeholders
+    ))?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(names), |row| row.get::<_, String>(0))?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
 pub fn list_sessions(conn: &Connection, filter: &Filter) -> Result<Vec<AiSession>> {
     let (where_sql, mut values) = filter.clauses("s");
     let sql = format!(
@@ -228,37 +253,41 @@ pub fn resolve_session(conn: &Connection, reference: &str) -> Result<Vec<AiSessi
 }
 
 /// Full-text search over indexed chunks, collapsed to one hit per session with
-/// its best-matching excerpt.
-pub fn search(conn: &Connection, query: &str, filter: &Filter) -> Result<Vec<AiSearchResult>> {
-    let match_expr = fts_query(query);
-    if match_expr.is_empty() {
+/// its best-matching excerpt, matched words marked. Title and project words
+/// count as configured, and [`rerank`] folds in recency and saved names.
+pub fn search(conn: &Connection, filter: &Filter) -> Result<Vec<AiSearchResult>> {
+    let Some(match_expr) = filter.query.match_expr() else {
         return Ok(Vec::new());
-    }
+    };
 
-    let (where_sql, mut values) = filter.clauses("s");
+    let (where_sql, filter_values) = filter.clauses("s");
     // Over-fetch chunks: many hits collapse into the same session.
     let chunk_limit = (filter.limit * 8).max(50) as i64;
 
-    let columns = SESSION_COLUMNS
-        .split(", ")
-        .map(|col| format!("s.{}", col.trim()))
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    // The FTS table is left unaliased: snippet() and rank must name it directly.
+    // The FTS table is left unaliased: snippet() and bm25() must name it directly.
     let sql = format!(
-        "SELECT {}, snippet(ai_chunks_fts, 0, '', '', '…', 26), ai_chunks_fts.rank
+        "SELECT {}, snippet(ai_chunks_fts, 0, '{}', '{}', '…', 26),
+                bm25(ai_chunks_fts, 1.0, ?, ?) AS score
          FROM ai_chunks_fts
          JOIN ai_chunks c ON c.id = ai_chunks_fts.rowid
          JOIN ai_sessions s ON s.uid = c.session_uid
          WHERE ai_chunks_fts MATCH ?{}
-         ORDER BY ai_chunks_fts.rank
+         ORDER BY score
          LIMIT ?",
-        columns, where_sql
+        session_columns("s"),
+        MATCH_START,
+        MATCH_END,
+        where_sql
     );
 
-    // Bound values follow the order the placeholders appear in: MATCH, filters, limit.
-    values.insert(0, SqlValue::Text(match_expr));
+    // Bound values follow the order the placeholders appear in: weights,
+    // MATCH, filters, limit.
+    let mut values = vec![
+        SqlValue::Real(filter.ranking.title_weight),
+        SqlValue::Real(filter.ranking.project_weight),
+        SqlValue::Text(match_expr),
+    ];
+    values.extend(filter_values);
     values.push(SqlValue::Integer(chunk_limit));
 
     let mut stmt = conn.prepare(&sql)?;
@@ -269,38 +298,61 @@ pub fn search(conn: &Connection, query: &str, filter: &Filter) -> Result<Vec<AiS
             rank: row.get(14)?,
         })
     })?;
+    let results = best_per_session(rows)?;
 
-    let mut seen = std::collections::HashSet::new();
-    let mut results = Vec::new();
-    for row in rows {
-        let result = row?;
-        // Rows arrive best-rank-first, so the first hit per session is its best.
-        if seen.insert(result.session.uid.clone()) {
-            results.push(result);
-            if results.len() >= filter.limit {
-                break;
-            }
-        }
-    }
-
-    Ok(results)
+    Ok(rerank(results, &filter.ranking, now_ms(), filter.limit))
 }
 
 /// Case-insensitive substring search, for when you remember a fragment rather
-/// than a word — FTS5 only matches whole tokens.
-pub fn search_fuzzy(conn: &Connection, query: &str, filter: &Filter) -> Result<Vec<AiSearchResult>> {
-    let needle = query.trim();
+/// than a word — FTS5 only matches whole tokens. Ranked by BM25 over the
+/// trigram index; a needle under three characters falls back to a scan.
+pub fn search_fuzzy(conn: &Connection, filter: &Filter) -> Result<Vec<AiSearchResult>> {
+    let Some(match_expr) = filter.query.substring_expr() else {
+        return scan_fuzzy(conn, filter);
+    };
+
+    let (where_sql, mut values) = filter.clauses("s");
+
+    // Trigram snippets count in characters, and 64 is FTS5's ceiling.
+    let sql = format!(
+        "SELECT {}, snippet(ai_chunks_trigram, 0, '{}', '{}', '…', 64), ai_chunks_trigram.rank
+         FROM ai_chunks_trigram
+         JOIN ai_chunks c ON c.id = ai_chunks_trigram.rowid
+         JOIN ai_sessions s ON s.uid = c.session_uid
+         WHERE ai_chunks_trigram MATCH ?{}
+         ORDER BY ai_chunks_trigram.rank
+         LIMIT ?",
+        session_columns("s"),
+        MATCH_START,
+        MATCH_END,
+        where_sql
+    );
+
+    values.insert(0, SqlValue::Text(match_expr));
+    values.push(SqlValue::Integer((filter.limit * 8).max(50) as i64));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(AiSearchResult {
+            session: session_from_row(row, 0)?,
+            snippet: row.get(13)?,
+            rank: row.get(14)?,
+        })
+    })?;
+    let results = best_per_session(rows)?;
+
+    Ok(rerank(results, &filter.ranking, now_ms(), filter.limit))
+}
+
+/// Substring search by `LIKE`, for needles too short for the trigram index.
+fn scan_fuzzy(conn: &Connection, filter: &Filter) -> Result<Vec<AiSearchResult>> {
+    let text = filter.query.text();
+    let needle = text.trim();
     if needle.is_empty() {
         return Ok(Vec::new());
     }
 
     let (where_sql, mut values) = filter.clauses("s");
-    let columns = SESSION_COLUMNS
-        .split(", ")
-        .map(|col| format!("s.{}", col.trim()))
-        .collect::<Vec<_>>()
-        .join(", ");
-
     let sql = format!(
         "SELECT {}, c.text
          FROM ai_chunks c
@@ -308,7 +360,8 @@ pub fn search_fuzzy(conn: &Connection, query: &str, filter: &Filter) -> Result<V
          WHERE c.text LIKE ? ESCAPE '\\'{}
          ORDER BY c.timestamp DESC
          LIMIT ?",
-        columns, where_sql
+        session_columns("s"),
+        where_sql
     );
 
     values.insert(0, SqlValue::Text(format!("%{}%", escape_like(needle))));
@@ -323,22 +376,66 @@ pub fn search_fuzzy(conn: &Connection, query: &str, filter: &Filter) -> Result<V
             rank: 0.0,
         })
     })?;
+    let mut results = best_per_session(rows)?;
+    results.truncate(filter.limit);
+
+    Ok(results)
+}
+
+fn session_columns(alias: &str) -> String {
+    SESSION_COLUMNS
+        .split(", ")
+        .map(|col| format!("{}.{}", alias, col.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
+/// Keep each session's first row. Rows arrive best first, so that is its best.
+fn best_per_session(
+    rows: impl Iterator<Item = rusqlite::Result<AiSearchResult>>,
+) -> Result<Vec<AiSearchResult>> {
     let mut seen = std::collections::HashSet::new();
     let mut results = Vec::new();
     for row in rows {
         let result = row?;
         if seen.insert(result.session.uid.clone()) {
             results.push(result);
-            if results.len() >= filter.limit {
-                break;
-            }
         }
     }
-
     Ok(results)
 }
 
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Order sessions by BM25 relevance scaled up for recent activity and for a
+/// name the user saved, keeping the best `limit`. `rank` stays lower-is-better.
+pub(crate) fn rerank(
+    mut results: Vec<AiSearchResult>,
+    ranking: &RankingConfig,
+    now: i64,
+    limit: usize,
+) -> Vec<AiSearchResult> {
+    for result in &mut results {
+        // BM25 comes back negated, so more relevant is more negative.
+        let relevance = -result.rank;
+        let age_days = (now - result.session.last_activity).max(0) as f64 / 86_400_000.0;
+        let half_life = ranking.recency_half_life_days.max(f64::MIN_POSITIVE);
+        let recency = 1.0 + ranking.recency_boost * 0.5f64.powf(age_days / half_life);
+        let named = if result.session.custom_name.is_some() {
+            ranking.named_boost
+        } else {
+            1.0
+        };
+        result.rank = -(relevance * recency * named);
+    }
+    // Stable, so ties keep BM25's order.
+    results.sort_by(|a, b| a.rank.partial_cmp(&b.rank).unwrap_or(std::cmp::Ordering::Equal));
+    results.truncate(limit);
+    results
+}
+
 fn escape_like(input: &str) -> String {
     input
         .replace('\\', "\\\\")
@@ -346,23 +443,34 @@ fn escape_like(input: &str) -> String {
         .replace('_', "\\_")
 }
 
-/// A short window of text centred on the match, so results read like FTS5 snippets.
+/// A short window of text centred on the match, marked, so results read like
+/// FTS5 snippets.
 fn excerpt_around(text: &str, needle: &str) -> String {
     const WINDOW: usize = 90;
     let lower_text = text.to_lowercase();
-    let position = lower_text.find(&needle.to_lowercase()).unwrap_or(0);
+    let found = lower_text.find(&needle.to_lowercase());
+    let position = found.unwrap_or(0);
 
     let chars: Vec<char> = text.chars().collect();
     // `position` is a byte offset; convert it to a char offset.
     let char_position = text[..position].chars().count();
+    let match_end = (char_position + needle.chars().count()).min(chars.len());
     let start = char_position.saturating_sub(WINDOW / 2);
-    let end = (char_position + needle.chars().count() + WINDOW / 2).min(chars.len());
+    let end = (match_end + WINDOW / 2).min(chars.len());
 
     let mut excerpt = String::new();
     if start > 0 {
         excerpt.push('…');
     }
-    excerpt.extend(chars[start..end].iter());
+    if found.is_some() {
+        excerpt.extend(chars[start..char_position].iter());
+        excerpt.push(MATCH_START);
+        excerpt.extend(chars[char_position..match_end].iter());
+        excerpt.push(MATCH_END);
+        excerpt.extend(chars[match_end..end].iter());
+    } else {
+        excerpt.extend(chars[start..end].iter());
+    }
     if end < chars.len() {
         excerpt.push('…');
     }
@@ -379,7 +487,7 @@ pub fn session_chunks(conn: &Connection, session_uid: &str) -> Result<Vec<Chunk>
         Ok(Chunk {
             chunk_id: row.get(0)?,
             session_uid: row.get(1)?,
-            source: Source::parse(&source).unwrap_or(Source::Claude),
+            source: Source::from_stored(&source),
             project: row.get(3)?,
             title: row.get(4)?,
             timestamp: row.get(5)?,
@@ -407,7 +515,7 @@ pub fn stats(conn: &Connection) -> Result<Stats> {
     )?;
 
     let mut per_source = Vec::new();
-    for source in Source::ALL {
+    for source in Source::all() {
         let count: i64 = conn.query_row(
             "SELECT COUNT(*) FROM ai_sessions WHERE source = ?1",
             params![source.as_str()],
@@ -427,7 +535,16 @@ pub fn stats(conn: &Connection) -> Result<Stats> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ai::models::session_uid;
+    use crate::ai::models::{session_uid, strip_matches};
+    use crate::search::query::parse;
+
+    fn searching(text: &str, limit: usize) -> Filter {
+        Filter {
+            query: parse(text).unwrap(),
+            limit,
+            ..Default::default()
+        }
+    }
 
     fn test_db() -> Connection {
         let conn = Connection::open_in_memory().unwrap();
@@ -479,9 +596,10 @@ mod tests {
     }
 
     #[test]
-    fn fts_query_quotes_every_token() {
-        assert_eq!(fts_query("recall --resume"), "\"recall\" AND \"--resume\"");
-        assert_eq!(fts_query("  "), "");
+    fn search_text_quotes_every_token() {
+        let query = parse("recall --resume").unwrap();
+        assert_eq!(query.match_expr().unwrap(), "\"recall\" AND \"--resume\"");
+        assert_eq!(parse("  ").unwrap().match_expr(), None);
     }
 
     #[test]
@@ -496,6 +614,21 @@ mod tests {
         assert_eq!(loaded.message_count, 2);
     }
 
+    #[test]
+    fn a_source_no_longer_configured_is_not_mistaken_for_claude() {
+        let conn = test_db();
+        let session = sample(Source::Custom("retired-tool"), "r1", "/repos/one", 1000);
+        upsert_session(&conn, &session, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&session, 0, "USER: hello")).unwrap();
+
+        let loaded = get_session(&conn, &session.uid).unwrap().unwrap();
+        assert_eq!(loaded.source, Source::Custom("retired-tool"));
+        assert_eq!(session_chunks(&conn, &session.uid).unwrap()[0].source, loaded.source);
+        // With nothing to hand it back to, resuming prints it.
+        let spec = crate::ai::resume::resume_command(&loaded, None);
+        assert_eq!(spec.args[1..], ["agents", "show", "retired-tool:r1"]);
+    }
+
     #[test]
     fn upsert_updates_rather_than_duplicates() {
         let conn = test_db();
@@ -541,12 +674,83 @@ mod tests {
         insert_chunk(&conn, &chunk_for(&session, 0, "USER: fix the flaky retry logic")).unwrap();
         insert_chunk(&conn, &chunk_for(&session, 1, "ASSISTANT: the retry logic now backs off")).unwrap();
 
-        let results = search(&conn, "retry", &Filter::with_limit(10)).unwrap();
+        let results = search(&conn, &searching("retry", 10)).unwrap();
         assert_eq!(results.len(), 1, "both chunks belong to one session");
         assert_eq!(results[0].session.uid, session.uid);
         assert!(results[0].snippet.to_lowercase().contains("retry"));
     }
 
+    #[test]
+    fn search_marks_the_matched_words() {
+        let conn = test_db();
+        let session = sample(Source::Claude, "abc", "/repos/one", 1000);
+        upsert_session(&conn, &session, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&session, 0, "USER: the retry keeps failing, retry again")).unwrap();
+
+        let results = search(&conn, &searching("retry", 10)).unwrap();
+        let marked = format!("{}retry{}", MATCH_START, MATCH_END);
+        assert_eq!(results[0].snippet.matches(&marked).count(), 2);
+        assert_eq!(strip_matches(&results[0].snippet), "USER: the retry keeps failing, retry again");
+    }
+
+    #[test]
+    fn a_title_match_outranks_the_same_word_in_the_body() {
+        let conn = test_db();
+        let mut titled = sample(Source::Claude, "titled", "/repos/one", 1000);
+        titled.title = Some("kafka consumer lag".into());
+        let mut mentioned = sample(Source::Claude, "mentioned", "/repos/one", 1000);
+        mentioned.title = Some("misc".into());
+        upsert_session(&conn, &titled, 1).unwrap();
+        upsert_session(&conn, &mentioned, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&titled, 0, "USER: the lag grows overnight")).unwrap();
+        insert_chunk(&conn, &chunk_for(&mentioned, 0, "USER: kafka is slow and the kafka lag grows")).unwrap();
+
+        let order = |ranking: RankingConfig| -> Vec<String> {
+            let filter = Filter {
+                ranking,
+                ..searching("kafka", 10)
+            };
+            search(&conn, &filter).unwrap().into_iter().map(|r| r.session.session_id).collect()
+        };
+        assert_eq!(order(RankingConfig::default()), vec!["titled", "mentioned"]);
+        let body_only = RankingConfig {
+            title_weight: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(order(body_only), vec!["mentioned", "titled"]);
+    }
+
+    #[test]
+    fn recent_and_named_sessions_rank_higher_among_equals() {
+        const DAY: i64 = 86_400_000;
+        let now = 1000 * DAY;
+        let hit = |id: &str, activity: i64, name: Option<&str>| {
+            let mut session = sample(Source::Claude, id, "/p", activity);
+            session.custom_name = name.map(String::from);
+            AiSearchResult {
+                session,
+                snippet: String::new(),
+                rank: -2.0,
+            }
+        };
+        let ids = |results: Vec<AiSearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.session.session_id).collect()
+        };
+        let ranking = RankingConfig::default();
+
+        let results = vec![hit("old", now - 365 * DAY, None), hit("new", now - DAY, None)];
+        assert_eq!(ids(rerank(results, &ranking, now, 10)), vec!["new", "old"]);
+
+        let results = vec![hit("plain", now, None), hit("named", now, Some("auth rewrite"))];
+        assert_eq!(ids(rerank(results, &ranking, now, 10)), vec!["named", "plain"]);
+
+        // Relevance still leads: a far better match is not buried by age.
+        let mut strong = hit("strong", now - 365 * DAY, None);
+        strong.rank = -10.0;
+        let results = vec![hit("weak", now, None), strong];
+        assert_eq!(ids(rerank(results, &ranking, now, 1)), vec!["strong"]);
+    }
+
     #[test]
     fn search_requires_all_tokens() {
         let conn = test_db();
@@ -554,8 +758,8 @@ mod tests {
         upsert_session(&conn, &session, 1).unwrap();
         insert_chunk(&conn, &chunk_for(&session, 0, "docker compose build")).unwrap();
 
-        assert_eq!(search(&conn, "docker compose", &Filter::with_limit(10)).unwrap().len(), 1);
-        assert!(search(&conn, "docker kubernetes", &Filter::with_limit(10)).unwrap().is_empty());
+        assert_eq!(search(&conn, &searching("docker compose", 10)).unwrap().len(), 1);
+        assert!(search(&conn, &searching("docker kubernetes", 10)).unwrap().is_empty());
     }
 
     #[test]
@@ -570,8 +774,8 @@ mod tests {
 
         let by_source = search(
             &conn,
-            "keyword",
             &Filter {
+                query: parse("keyword").unwrap(),
                 source: Some(Source::Codex),
                 limit: 10,
                 ..Default::default()
@@ -583,8 +787,8 @@ mod tests {
 
         let by_project = search(
             &conn,
-            "keyword",
             &Filter {
+                query: parse("keyword").unwrap(),
                 project: Some("alpha".into()),
                 limit: 10,
                 ..Default::default()
@@ -595,16 +799,43 @@ mod tests {
         assert_eq!(by_project[0].session.project, "/repos/alpha");
     }
 
+    #[test]
+    fn query_filters_and_exclusions_apply_per_session() {
+        let conn = test_db();
+        let mut opus = sample(Source::Claude, "a", "/repos/api", 1000);
+        opus.model = Some("claude-opus-4".into());
+        let codex = sample(Source::Codex, "b", "/repos/web", 2000);
+        upsert_session(&conn, &opus, 1).unwrap();
+        upsert_session(&conn, &codex, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&opus, 0, "migrate the schema")).unwrap();
+        insert_chunk(&conn, &chunk_for(&opus, 1, "then drop the flaky test")).unwrap();
+        insert_chunk(&conn, &chunk_for(&codex, 0, "migrate the schema too")).unwrap();
+
+        assert_eq!(search(&conn, &searching("migrate", 10)).unwrap().len(), 2);
+        let opus_only = search(&conn, &searching("migrate model:opus", 10)).unwrap();
+        assert_eq!(opus_only.len(), 1);
+        assert_eq!(opus_only[0].session.uid, opus.uid);
+        assert_eq!(search(&conn, &searching("migrate repo:web", 10)).unwrap().len(), 1);
+
+        // Excluded text anywhere in a session drops the whole session.
+        let rest = search(&conn, &searching("migrate -flaky", 10)).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].session.uid, codex.uid);
+
+        assert!(list_sessions(&conn, &searching("exit:1", 10)).unwrap().is_empty());
+        assert_eq!(list_sessions(&conn, &searching("source:codex", 10)).unwrap().len(), 1);
+    }
+
     #[test]
     fn deleting_a_session_removes_it_from_the_index() {
         let conn = test_db();
         let session = sample(Source::Claude, "abc", "/repos/one", 1000);
         upsert_session(&conn, &session, 1).unwrap();
         insert_chunk(&conn, &chunk_for(&session, 0, "unique-token-xyz")).unwrap();
-        assert_eq!(search(&conn, "unique-token-xyz", &Filter::with_limit(5)).unwrap().len(), 1);
+        assert_eq!(search(&conn, &searching("unique-token-xyz", 5)).unwrap().len(), 1);
 
         delete_session(&conn, &session.uid).unwrap();
-        assert!(search(&conn, "unique-token-xyz", &Filter::with_limit(5)).unwrap().is_empty());
+        assert!(search(&conn, &searching("unique-token-xyz", 5)).unwrap().is_empty());
         assert_eq!(stats(&conn).unwrap().sessions, 0);
     }
 
@@ -618,8 +849,8 @@ mod tests {
         delete_chunks(&conn, &session.uid).unwrap();
         insert_chunk(&conn, &chunk_for(&session, 0, "new-token")).unwrap();
 
-        assert!(search(&conn, "old-token", &Filter::with_limit(5)).unwrap().is_empty());
-        assert_eq!(search(&conn, "new-token", &Filter::with_limit(5)).unwrap().len(), 1);
+        assert!(search(&conn, &searching("old-token", 5)).unwrap().is_empty());
+        assert_eq!(search(&conn, &searching("new-token", 5)).unwrap().len(), 1);
     }
 
     #[test]
@@ -630,10 +861,41 @@ mod tests {
         insert_chunk(&conn, &chunk_for(&session, 0, "we refactored the SessionIndexer today")).unwrap();
 
         // FTS5 tokenizes on word boundaries, so a mid-word fragment misses.
-        assert!(search(&conn, "ionIndex", &Filter::with_limit(5)).unwrap().is_empty());
-        let fuzzy = search_fuzzy(&conn, "ionIndex", &Filter::with_limit(5)).unwrap();
+        assert!(search(&conn, &searching("ionIndex", 5)).unwrap().is_empty());
+        let fuzzy = search_fuzzy(&conn, &searching("ionIndex", 5)).unwrap();
+        assert_eq!(fuzzy.len(), 1);
+        let marked = format!("Sess{}ionIndex{}er", MATCH_START, MATCH_END);
+        assert!(fuzzy[0].snippet.contains(&marked), "the fragment is marked");
+    }
+
+    #[test]
+    fn fuzzy_search_ranks_by_how_often_the_fragment_appears() {
+        let conn = test_db();
+        let once = sample(Source::Claude, "once", "/p", 2000);
+        let often = sample(Source::Claude, "often", "/p", 1000);
+        upsert_session(&conn, &once, 1).unwrap();
+        upsert_session(&conn, &often, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&once, 0, "the retrier gave up")).unwrap();
+        insert_chunk(&conn, &chunk_for(&often, 0, "retrier, retrier: the retrier loops")).unwrap();
+
+        let fuzzy = search_fuzzy(&conn, &searching("trier", 5)).unwrap();
+        let ids: Vec<&str> = fuzzy.iter().map(|r| r.session.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["often", "once"]);
+        assert!(fuzzy[0].rank < fuzzy[1].rank);
+    }
+
+    #[test]
+    fn fuzzy_search_finds_text_without_word_breaks() {
+        let conn = test_db();
+        let session = sample(Source::Claude, "cjk", "/p", 1000);
+        upsert_session(&conn, &session, 1).unwrap();
+        insert_chunk(&conn, &chunk_for(&session, 0, "全文検索エンジンを作る")).unwrap();
+
+        assert!(search(&conn, &searching("検索エンジン", 5)).unwrap().is_empty());
+        let fuzzy = search_fuzzy(&conn, &searching("検索エンジン", 5)).unwrap();
         assert_eq!(fuzzy.len(), 1);
-        assert!(fuzzy[0].snippet.contains("SessionIndexer"));
+        // Shorter than a trigram: still found, by a scan.
+        assert_eq!(search_fuzzy(&conn, &searching("検索", 5)).unwrap().len(), 1);
     }
 
     #[test]
@@ -643,7 +905,7 @@ mod tests {
         upsert_session(&conn, &session, 1).unwrap();
         insert_chunk(&conn, &chunk_for(&session, 0, "plain text without wildcards")).unwrap();
 
-        assert!(search_fuzzy(&conn, "%", &Filter::with_limit(5)).unwrap().is_empty());
+        assert!(search_fuzzy(&conn, &searching("%", 5)).unwrap().is_empty());
     }
 
     #[test]
@@ -657,6 +919,26 @@ mod tests {
         assert_eq!(listed[0].session_id, "new");
     }
 
+    #[test]
+    fn a_time_window_keeps_sessions_that_overlap_it() {
+        let conn = test_db();
+        let mut long = sample(Source::Claude, "long", "/p", 1000);
+        long.last_activity = 5000;
+        upsert_session(&conn, &long, 1).unwrap();
+        upsert_session(&conn, &sample(Source::Codex, "early", "/p", 500), 1).unwrap();
+        upsert_session(&conn, &sample(Source::Codex, "late", "/p", 9000), 1).unwrap();
+
+        let window = Filter {
+            since: Some(4000),
+            until: Some(6000),
+            limit: 10,
+            ..Default::default()
+        };
+        let listed = list_sessions(&conn, &window).unwrap();
+        assert_eq!(listed.len(), 1, "still running when the window opened");
+        assert_eq!(listed[0].session_id, "long");
+    }
+
     #[test]
     fn resolve_session_accepts_uid_native_id_and_prefix() {
         let conn = test_db();
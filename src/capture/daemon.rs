@@ -0,0 +1,295 @@
+//! `recall daemon` — an optional long-running writer for shell hooks.
+//!
+//! Without it every prompt pays for a `recall log` that loads config, opens
+//! SQLite, checks the schema and forks `git` twice, and a dozen busy shells
+//! contend on the database file. With it running, `recall log` only connects
+//! to a Unix socket and writes one JSON line; the daemon batches those into a
+//! single transaction and remembers what `git` said about each directory.
+//!
+//! Nothing depends on it: when the socket is missing or nobody answers,
+//! `recall log` writes directly, exactly as before.
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use crate::capture::context;
+use crate::capture::log::{self, LogRecord};
+use crate::config::settings::{daemon_socket, ensure_recall_dir, load_config, Config};
+use crate::db::queries;
+use crate::db::schema::open_db;
+use crate::privacy::redact::Redactor;
+
+/// How long the writer waits for more records before committing a batch.
+const BATCH_WINDOW: Duration = Duration::from_millis(250);
+const MAX_BATCH: usize = 500;
+
+/// How long a directory's repo and branch are trusted. Short, because a
+/// branch can change under a directory from another terminal.
+const GIT_TTL: Duration = Duration::from_secs(10);
+
+/// A hook that cannot reach the daemon this quickly writes directly instead.
+const CLIENT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Hand a record to a running daemon. Any error means the caller should
+/// fall back to writing the record itself.
+pub fn send(socket: &Path, record: &LogRecord) -> Result<()> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    Ok(())
+}
+
+pub fn run() -> Result<()> {
+    ensure_recall_dir()?;
+    let socket = daemon_socket();
+
+    if UnixStream::connect(&socket).is_ok() {
+        bail!("recall daemon is already running on {}", socket.display());
+    }
+    // Left behind by a daemon that did not exit cleanly.
+    let _ = std::fs::remove_file(&socket);
+
+    let listener = UnixListener::bind(&socket)
+        .with_context(|| format!("Failed to listen on {}", socket.display()))?;
+    std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+
+    // Open (and migrate) the database once, up front, so a broken database
+    // is reported here rather than swallowed by the writer thread.
+    let conn = open_db()?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || write_batches(conn, rx));
+
+    println!(
+        "  {} {} {}",
+        "◉".cyan(),
+        "recall daemon".bold(),
+        format!("listening on {}", socket.display()).dimmed()
+    );
+    println!(
+        "  {}",
+        "Shell hooks hand their commands here while it runs. Ctrl-C to stop.".dimmed()
+    );
+
+    serve(&listener, &tx);
+    Ok(())
+}
+
+/// Accept connections until the listener fails, queueing every record read.
+fn serve(listener: &UnixListener, tx: &Sender<(LogRecord, i64)>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        // Clients write a line and hang up; a stalled one must not hold up
+        // the next prompt's record.
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+        for record in read_records(stream) {
+            // Stamped on arrival: this is when the command finished, however
+            // long the record then waits for its batch.
+            let received = Utc::now().timestamp_millis();
+            if tx.send((record, received)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn read_records(stream: UnixStream) -> Vec<LogRecord> {
+    BufReader::new(stream)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+fn write_batches(mut conn: rusqlite::Connection, rx: Receiver<(LogRecord, i64)>) {
+    let mut git = GitCache::default();
+
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + BATCH_WINDOW;
+        while batch.len() < MAX_BATCH {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match rx.recv_timeout(wait) {
+                Ok(next) => batch.push(next),
+                Err(_) => break,
+            }
+        }
+
+        // Re-read every batch, so edits to ignore and redact patterns apply
+        // without a restart.
+        if let Err(e) = write_batch(&mut conn, &mut git, &batch, load_config()) {
+            eprintln!("recall daemon: dropped {} commands: {:#}", batch.len(), e);
+        }
+    }
+}
+
+fn write_batch(
+    conn: &mut rusqlite::Connection,
+    git: &mut GitCache,
+    batch: &[(LogRecord, i64)],
+    config: Result<Config>,
+) -> Result<()> {
+    // A config that does not parse, or a broken pattern, drops the batch:
+    // storing it unredacted is the one outcome that cannot be undone.
+    let config = config?;
+    let redactor = Redactor::from_config(&config.privacy)?;
+
+    let tx = conn.transaction()?;
+    for (record, received) in batch {
+        let prepared = log::prepare(record, *received, &config, &redactor, |dir| {
+            git.lookup(dir, &record.command)
+        });
+        if let Some((session, cmd)) = prepared {
+            queries::insert_session(&tx, &session)?;
+            queries::insert_command(&tx, &cmd)?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Repo and branch per directory, so a burst of commands in one place costs
+/// two `git` forks rather than two each.
+#[derive(Default)]
+struct GitCache {
+    entries: HashMap<String, (Instant, Option<String>, Option<String>)>,
+}
+
+impl GitCache {
+    fn lookup(&mut self, dir: &str, command: &str) -> (Option<String>, Option<String>) {
+        self.lookup_with(dir, command, |dir| {
+            (context::detect_git_repo(dir), context::detect_git_branch(dir))
+        })
+    }
+
+    fn lookup_with(
+        &mut self,
+        dir: &str,
+        command: &str,
+        detect: impl FnOnce(&str) -> (Option<String>, Option<String>),
+    ) -> (Option<String>, Option<String>) {
+        // `git checkout`, `git switch`, `git init` and friends are exactly the
+        // commands that change the answer.
+        let stale_after_command = command.trim_start().starts_with("git ");
+        if let Some((at, repo, branch)) = self.entries.get(dir) {
+            if !stale_after_command && at.elapsed() < GIT_TTL {
+                return (repo.clone(), branch.clone());
+            }
+        }
+
+        let (repo, branch) = detect(dir);
+        self.entries
+            .insert(dir.to_string(), (Instant::now(), repo.clone(), branch.clone()));
+        (repo, branch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_socket(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "recall-daemon-{}-{}.sock",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn a_record_survives_the_trip_through_the_socket() {
+        let path = temp_socket("trip");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let record = LogRecord {
+            command: "cargo test".into(),
+            exit_code: Some(101),
+            duration_ms: Some(2_500),
+            cwd: Some("/tmp".into()),
+            session: "s1".into(),
+            ..LogRecord::default()
+        };
+        send(&path, &record).unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let received = read_records(stream);
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].command, "cargo test");
+        assert_eq!(received[0].exit_code, Some(101));
+        assert_eq!(received[0].duration_ms, Some(2_500));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn sending_without_a_daemon_fails_so_the_hook_writes_directly() {
+        let path = temp_socket("absent");
+        assert!(send(&path, &LogRecord::default()).is_err());
+    }
+
+    #[test]
+    fn git_lookups_are_cached_per_directory() {
+        let mut cache = GitCache::default();
+        let calls = std::cell::Cell::new(0);
+        let detect = |_: &str| {
+            calls.set(calls.get() + 1);
+            (Some("recall".into()), Some("main".into()))
+        };
+
+        cache.lookup_with("/src/recall", "ls", detect);
+        cache.lookup_with("/src/recall", "cargo build", detect);
+        let (_, branch) = cache.lookup_with("/src/recall", "make", detect);
+        assert_eq!(branch.as_deref(), Some("main"));
+        cache.lookup_with("/src/other", "ls", detect);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn a_git_command_refreshes_the_cached_branch() {
+        let mut cache = GitCache::default();
+        cache.lookup_with("/src/recall", "ls", |_| (None, Some("main".into())));
+        let (_, branch) =
+            cache.lookup_with("/src/recall", "git switch dev", |_| (None, Some("dev".into())));
+        assert_eq!(branch.as_deref(), Some("dev"));
+    }
+
+    #[test]
+    fn a_config_that_does_not_parse_drops_the_batch() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        let mut git = GitCache::default();
+        let batch = vec![(
+            LogRecord {
+                command: "cargo build".into(),
+                session: "s1".into(),
+                ..LogRecord::default()
+            },
+            1_772_359_200_000,
+        )];
+        let stored = |conn: &rusqlite::Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM commands", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        let malformed = toml::from_str::<Config>("[privacy\nredact_patterns = [")
+            .context("Failed to parse config.toml");
+        assert!(write_batch(&mut conn, &mut git, &batch, malformed).is_err());
+        assert_eq!(stored(&conn), 0);
+
+        write_batch(&mut conn, &mut git, &batch, Ok(Config::default())).unwrap();
+        assert_eq!(stored(&conn), 1);
+    }
+}
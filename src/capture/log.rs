@@ -1,13 +1,17 @@
 use anyhow::Result;
 use chrono::Utc;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
-use crate::capture::context;
-use crate::config::settings::load_config;
+use crate::capture::{context, daemon};
+use crate::config::settings::{daemon_socket, load_config, Config};
 use crate::db::models::{Command, Session};
 use crate::db::queries;
 use crate::db::schema::open_db;
 use crate::privacy::filter::should_ignore;
+use crate::privacy::incognito;
+use crate::privacy::redact::Redactor;
 
 const MAX_OUTPUT_BYTES: usize = 10 * 1024; // 10KB
 
@@ -42,73 +46,119 @@ fn read_output_file(path: &str) -> Option<String> {
     }
 }
 
-pub fn handle_log(
-    command: &str,
-    exit_code: Option<i32>,
-    start_ms: Option<i64>,
-    cwd: Option<&str>,
-    session_id: &str,
-    terminal: Option<&str>,
-    output_file: Option<&str>,
-) -> Result<()> {
-    let config = load_config()?;
+/// One finished command, as a shell hook reports it. This is also the line
+/// `recall log` hands the daemon, so it stays flat and serializable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub start_ms: Option<i64>,
+    /// Set by hooks whose shell times commands itself (fish); the others pass
+    /// a start time and the duration is measured when the record arrives.
+    pub duration_ms: Option<i64>,
+    pub cwd: Option<String>,
+    pub session: String,
+    pub terminal: Option<String>,
+    pub output_file: Option<String>,
+}
 
-    // Check privacy filters
-    if should_ignore(command, &config.privacy.ignore_patterns) {
-        // Still clean up the temp file if present
-        if let Some(path) = output_file {
-            let _ = std::fs::remove_file(path);
-        }
+/// Record a command: through the daemon when one is listening, otherwise
+/// straight into the database.
+pub fn handle_log(record: &LogRecord) -> Result<()> {
+    if daemon::send(&daemon_socket(), record).is_ok() {
         return Ok(());
     }
 
-    // Check if paused
-    if crate::config::settings::pause_file().exists() {
+    let config = load_config()?;
+    let redactor = Redactor::from_config(&config.privacy)?;
+    let now = Utc::now().timestamp_millis();
+    let prepared = prepare(record, now, &config, &redactor, |dir| {
+        (context::detect_git_repo(dir), context::detect_git_branch(dir))
+    });
+    let (session, cmd) = match prepared {
+        Some(rows) => rows,
+        None => return Ok(()),
+    };
+
+    let conn = open_db()?;
+    queries::insert_session(&conn, &session)?;
+    queries::insert_command(&conn, &cmd)?;
+    Ok(())
+}
+
+/// Stamp a session's end time when its shell exits.
+pub fn handle_end_session(session_id: &str) -> Result<()> {
+    // Incognito lasts as long as the shell; its id is never reused.
+    incognito::set_incognito(session_id, false)?;
+    let conn = open_db()?;
+    queries::end_session(&conn, session_id, Utc::now().timestamp_millis())
+}
+
+/// Turn a record into the rows to insert, or `None` when it should not be kept
+/// (an ignored command or directory, an incognito shell, or recording is
+/// paused). `now` is when the command
+/// finished, which is not necessarily now for a record that sat in a batch.
+/// Either way the output file is consumed. Secrets are redacted from the
+/// command and its output here, so nothing upstream of the database sees them.
+pub fn prepare(
+    record: &LogRecord,
+    now: i64,
+    config: &Config,
+    redactor: &Redactor,
+    git: impl FnOnce(&str) -> (Option<String>, Option<String>),
+) -> Option<(Session, Command)> {
+    let output_file = record.output_file.as_deref();
+
+    let ignored_dir = record
+        .cwd
+        .as_deref()
+        .is_some_and(|dir| incognito::is_ignored_dir(Path::new(dir), &config.privacy.ignore_dirs));
+    if should_ignore(&record.command, &config.privacy.ignore_patterns)
+        || ignored_dir
+        || incognito::is_incognito(&record.session)
+        || crate::config::settings::pause_file().exists()
+    {
+        // Still clean up the temp file if present
         if let Some(path) = output_file {
             let _ = std::fs::remove_file(path);
         }
-        return Ok(());
+        return None;
     }
 
-    let now = Utc::now().timestamp_millis();
-    let duration_ms = start_ms.map(|s| now - s);
+    // A hook passes whichever it knows; the other follows from now.
+    let start_ms = record
+        .start_ms
+        .or_else(|| record.duration_ms.map(|d| now - d));
+    let duration_ms = record.duration_ms.or_else(|| start_ms.map(|s| now - s));
 
-    // Detect git context
-    let (git_repo, git_branch) = if let Some(dir) = cwd {
-        (context::detect_git_repo(dir), context::detect_git_branch(dir))
-    } else {
-        (None, None)
+    let (git_repo, git_branch) = match record.cwd.as_deref() {
+        Some(dir) => git(dir),
+        None => (None, None),
     };
 
     // Read and process output file
-    let output = output_file.and_then(read_output_file);
-
-    let conn = open_db()?;
+    let output = output_file
+        .and_then(read_output_file)
+        .map(|text| redactor.redact(&text).0.into_owned());
 
-    // Ensure session exists
     let session = Session {
-        id: session_id.to_string(),
+        id: record.session.clone(),
         start_time: start_ms.unwrap_or(now),
         end_time: None,
-        terminal_app: terminal.map(|s| s.to_string()),
-        initial_dir: cwd.map(|s| s.to_string()),
+        terminal_app: record.terminal.clone(),
+        initial_dir: record.cwd.clone(),
     };
-    queries::insert_session(&conn, &session)?;
-
-    // Insert command
     let cmd = Command {
         id: None,
-        session_id: session_id.to_string(),
-        command_text: command.to_string(),
+        session_id: record.session.clone(),
+        command_text: redactor.redact(&record.command).0.into_owned(),
         timestamp: start_ms.unwrap_or(now),
         duration_ms,
-        cwd: cwd.map(|s| s.to_string()),
+        cwd: record.cwd.clone(),
         git_repo,
         git_branch,
-        exit_code,
+        exit_code: record.exit_code,
         output,
     };
-    queries::insert_command(&conn, &cmd)?;
-
-    Ok(())
+    Some((session, cmd))
 }
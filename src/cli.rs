@@ -1,4 +1,5 @@
 use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(
@@ -21,18 +22,24 @@ pub enum Commands {
     Setup,
     /// Initialize shell hook (e.g., `eval "$(recall init zsh)"`)
     Init {
-        /// Shell type
+        /// Shell type: zsh, bash or fish
         shell: String,
     },
     /// Log a command (called by the shell hook)
     #[command(hide = true)]
     Log {
+        #[arg(long, required_unless_present = "end_session")]
+        command: Option<String>,
+        /// The shell is exiting: stamp the session's end time instead
         #[arg(long)]
-        command: String,
+        end_session: bool,
         #[arg(long)]
         exit_code: Option<i32>,
         #[arg(long)]
         start: Option<i64>,
+        /// Duration in milliseconds, from shells that time commands themselves
+        #[arg(long)]
+        duration: Option<i64>,
         #[arg(long)]
         cwd: Option<String>,
         #[arg(long)]
@@ -45,9 +52,17 @@ pub enum Commands {
     /// Generate a new session ID
     #[command(hide = true)]
     SessionId,
+    /// Search commands, agent sessions and summaries together, best match first
+    Find {
+        /// Search query: words, "phrases", -excluded, and filters like repo:api since:monday
+        query: String,
+        /// Max results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
     /// Search command history
     Search {
-        /// Search query
+        /// Search query: words, "phrases", -excluded, and filters like repo:api exit:!0
         query: String,
         /// Filter by git repo name
         #[arg(long)]
@@ -58,13 +73,30 @@ pub enum Commands {
         /// Show only failed commands
         #[arg(long)]
         failed: bool,
+        /// Only commands from this time on (2026-09-01, yesterday, "last monday", "3 days ago")
+        #[arg(long)]
+        since: Option<String>,
+        /// Only commands up to this time (a day, like yesterday, is included)
+        #[arg(long)]
+        until: Option<String>,
         /// Max results
         #[arg(long, default_value = "20")]
         limit: usize,
+        /// Skip this many results, for the next page
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        /// Also find commands by meaning, with the local embedding model
+        #[arg(long)]
+        hybrid: bool,
+        /// Match the text anywhere, mid-word included
+        #[arg(long, conflicts_with = "hybrid")]
+        fuzzy: bool,
     },
     /// Show today's commands
     Today,
-    /// Show commands on a specific date (YYYY-MM-DD)
+    /// Show this week's commands
+    Week,
+    /// Show commands on a day or over a range ("last monday", "last week", 2026-09-01..2026-09-07)
     On {
         date: String,
     },
@@ -72,13 +104,83 @@ pub enum Commands {
     Pause,
     /// Resume recording
     Resume,
+    /// Stop recording in this shell only, until it exits
+    Incognito {
+        /// Start recording this shell again
+        #[arg(long)]
+        off: bool,
+    },
     /// Summarize unsummarized sessions using LLM
     Summarize,
-    /// Search and resume your AI agent sessions (Claude Code, Codex)
+    /// List and search session summaries, by tag and intent too
+    Summaries {
+        /// Search query; without one, the newest summaries
+        query: Option<String>,
+        /// Only summaries with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only summaries with this intent (debugging, deployment, …)
+        #[arg(long)]
+        intent: Option<String>,
+        /// Only work from this time on (2026-09-01, yesterday, "last monday")
+        #[arg(long)]
+        since: Option<String>,
+        /// Only work up to this time (a day, like yesterday, is included)
+        #[arg(long)]
+        until: Option<String>,
+        /// Max results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// List the tags and intents in use, with how many summaries have each
+        #[arg(long, conflicts_with_all = ["query", "tag", "intent", "since", "until"])]
+        tags: bool,
+    },
+    /// Permanently delete commands from history, search index and summaries
+    Forget {
+        /// Forget commands whose text or output contains this
+        pattern: Option<String>,
+        /// Forget the command with this id (repeatable)
+        #[arg(long)]
+        id: Vec<i64>,
+        /// Forget a shell session (id or unique prefix)
+        #[arg(long)]
+        session: Option<String>,
+        /// Only commands from this time on (2026-09-01, 2026-09-01 14:30, yesterday, "3 days ago")
+        #[arg(long)]
+        since: Option<String>,
+        /// Only commands up to this time (a day, like "last monday", is included)
+        #[arg(long)]
+        until: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Scan stored history and agent sessions for secrets, and offer to redact them
+    Audit {
+        /// Redact everything found without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Encrypt the database with the key from RECALL_DB_KEY or [database] key_command
+    Encrypt,
+    /// Decrypt the database back to plain SQLite
+    Decrypt,
+    /// Apply [retention] limits from config.toml and compact the database
+    Gc,
+    /// Embed new commands and agent sessions with the local model, for --hybrid search
+    Embed,
+    /// Search and resume your AI agent sessions (Claude Code, Codex, Gemini CLI, Aider)
     Agents {
         #[command(subcommand)]
         command: Option<AgentsCommand>,
     },
+    /// Bring conversations from elsewhere into the index
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+    /// Run the capture daemon, so shell hooks skip the per-command database work
+    Daemon,
     /// Open the interactive TUI
     Ui,
     /// Open the web graph view
@@ -91,7 +193,7 @@ pub enum Commands {
 
 #[derive(Subcommand)]
 pub enum AgentsCommand {
-    /// Scan Claude Code and Codex transcripts and update the index
+    /// Scan Claude Code, Codex, Gemini CLI and Aider transcripts and update the index
     Index {
         /// Re-read every transcript, even unchanged ones
         #[arg(long)]
@@ -107,8 +209,11 @@ pub enum AgentsCommand {
         #[command(flatten)]
         filters: AgentFilters,
         /// Match substrings instead of whole words
-        #[arg(long)]
+        #[arg(long, conflicts_with = "hybrid")]
         fuzzy: bool,
+        /// Also find sessions by meaning, with the local embedding model
+        #[arg(long)]
+        hybrid: bool,
     },
     /// List indexed agent sessions, newest first
     List {
@@ -135,14 +240,29 @@ pub enum AgentsCommand {
     Stats,
 }
 
+#[derive(Subcommand)]
+pub enum ImportCommand {
+    /// Import a ChatGPT or Claude.ai data export, so its chats show up in searches
+    ChatExport {
+        /// The export zip, the folder it unpacks to, or its conversations.json
+        path: PathBuf,
+    },
+}
+
 #[derive(Args)]
 pub struct AgentFilters {
-    /// Filter by source (claude or codex)
+    /// Filter by source (claude, codex, gemini, aider, chat, or a [[sources]] name)
     #[arg(long)]
     pub source: Option<String>,
     /// Filter by project path substring
     #[arg(long)]
     pub project: Option<String>,
+    /// Only sessions active from this time on (2026-09-01, yesterday, "last monday", "3 days ago")
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Only sessions started up to this time (a day, like yesterday, is included)
+    #[arg(long)]
+    pub until: Option<String>,
     /// Max results
     #[arg(long, default_value = "20")]
     pub limit: usize,
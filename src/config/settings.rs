@@ -2,20 +2,195 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default)]
     pub privacy: PrivacyConfig,
     #[serde(default)]
     pub llm: LlmConfig,
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub embeddings: EmbeddingsConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub aider: AiderConfig,
+    /// Agent tools recall has no parser for, described by where their JSONL
+    /// transcripts live and which fields hold what.
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SearchConfig {
+    /// When a search finds nothing but a corrected spelling would, show
+    /// those results straight away instead of suggesting it.
+    #[serde(default)]
+    pub autocorrect: bool,
+    #[serde(default)]
+    pub ranking: RankingConfig,
+}
+
+/// How agent sessions are ordered in search results, under
+/// `[search.ranking]`. BM25 relevance is the base; the rest scale it.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RankingConfig {
+    /// A word in a session's title — its saved name when it has one —
+    /// counts this many times a word in the conversation.
+    #[serde(default = "default_title_weight")]
+    pub title_weight: f64,
+    /// Likewise for a word in the project path.
+    #[serde(default = "default_project_weight")]
+    pub project_weight: f64,
+    /// How much more a session active just now is worth than an old one:
+    /// 0.5 lifts today's sessions by half. 0 turns recency off.
+    #[serde(default = "default_recency_boost")]
+    pub recency_boost: f64,
+    /// Days for the recency lift to fall by half.
+    #[serde(default = "default_recency_half_life")]
+    pub recency_half_life_days: f64,
+    /// Multiplier for sessions the user named in the tool. 1 turns it off.
+    #[serde(default = "default_named_boost")]
+    pub named_boost: f64,
+}
+
+/// Aider keeps its chat log in each repo rather than in one place, so recall
+/// looks for it under these directories. A `~` prefix means the home
+/// directory. Nothing is searched unless this is set.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AiderConfig {
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
+/// One `[[sources]]` entry: a tool that writes a JSON object per line, or a
+/// program that speaks for one. Field locations are JSON pointers into each
+/// line, such as `/payload/text`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SourceConfig {
+    /// What `--source` and `source:` call it, and the prefix of its ids.
+    pub name: String,
+    /// How it is shown. Defaults to the name.
+    pub label: Option<String>,
+    /// The transcripts, one session per file: `~/.acme/sessions/**/*.jsonl`.
+    pub glob: Option<String>,
+    /// Instead of a glob, a program recall runs as `<command> list` and
+    /// `<command> load <id>`, reading sessions and messages from its output.
+    pub command: Option<String>,
+    /// Seconds the command may take before it is stopped. Defaults to 10.
+    pub timeout_secs: Option<u64>,
+    /// Where the session id is. Defaults to the file name.
+    pub session_id: Option<String>,
+    /// Where the working directory is.
+    pub cwd: Option<String>,
+    /// Where a line's time is: RFC 3339, or seconds or milliseconds since
+    /// the epoch.
+    pub timestamp: Option<String>,
+    /// Where the speaker is: `user`, or `assistant` and its synonyms.
+    pub role: Option<String>,
+    /// Where the message is: a string, or a list of parts with `text`.
+    pub text: Option<String>,
+    /// Where a line's type is, for `keep` to look at.
+    pub event_type: Option<String>,
+    /// The types worth reading. Empty keeps every line.
+    #[serde(default)]
+    pub keep: Vec<String>,
+    /// The command that reopens a session, with `{id}`, `{cwd}` and `{file}`
+    /// filled in. Without one, resuming prints the transcript.
+    pub resume: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DatabaseConfig {
+    /// A shell command that prints the database encryption key, for keeping
+    /// it in a keychain or password manager rather than in `~/.recall/env`.
+    pub key_command: Option<String>,
+}
+
+/// A local embedding model for semantic search. Off unless `endpoint` is set.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EmbeddingsConfig {
+    /// Base URL of an OpenAI-compatible embeddings API, such as Ollama's
+    /// `http://localhost:11434/v1`.
+    pub endpoint: Option<String>,
+    #[serde(default = "default_embedding_model")]
+    pub model: String,
+    /// Texts sent per request while embedding the backlog.
+    #[serde(default = "default_embedding_batch")]
+    pub batch_size: usize,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PrivacyConfig {
     #[serde(default = "default_ignore_patterns")]
     pub ignore_patterns: Vec<String>,
+    /// Regexes whose matches are replaced before a command or its output is
+    /// stored. A group named `secret` limits the replacement to that group.
     #[serde(default = "default_redact_patterns")]
     pub redact_patterns: Vec<String>,
+    /// Redact common credential shapes (cloud keys, tokens, JWTs, passwords
+    /// in URLs) without listing them.
+    #[serde(default = "default_true")]
+    pub detect_credentials: bool,
+    /// Directories whose commands, and their subdirectories', are never
+    /// recorded. A `~` prefix means the home directory.
+    #[serde(default)]
+    pub ignore_dirs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SessionsConfig {
+    /// A shell idle for longer than this starts a new work session, so a
+    /// terminal left open for days is not one endless session.
+    #[serde(default = "default_idle_gap_minutes")]
+    pub idle_gap_minutes: u64,
+}
+
+impl SessionsConfig {
+    pub fn idle_gap_ms(&self) -> i64 {
+        self.idle_gap_minutes as i64 * 60_000
+    }
+}
+
+/// How long history is kept. Everything is kept forever unless set.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RetentionConfig {
+    /// Drop the captured output of commands older than this many days,
+    /// keeping the commands themselves.
+    pub output_days: Option<u64>,
+    /// Delete commands older than this many days. Summaries are kept.
+    pub command_days: Option<u64>,
+    /// Commands matching any of these (glob patterns, as in
+    /// `ignore_patterns`) are never deleted for their age or for space.
+    #[serde(default)]
+    pub keep_patterns: Vec<String>,
+    /// Drop the searchable text of agent sessions idle for this many days.
+    /// They stay listed and resumable; the transcripts are not touched.
+    pub agent_days: Option<u64>,
+    /// Once the database is bigger than this, trim the oldest output, then
+    /// the oldest commands, until it fits.
+    pub max_db_mb: Option<u64>,
+}
+
+impl RetentionConfig {
+    /// Whether any limit is configured, and so whether there is anything for
+    /// an automatic clean-up to do.
+    pub fn is_set(&self) -> bool {
+        self.output_days.is_some()
+            || self.command_days.is_some()
+            || self.agent_days.is_some()
+            || self.max_db_mb.is_some()
+    }
+
+    /// The oldest timestamp a limit of `days` keeps, in epoch milliseconds.
+    pub fn cutoff(days: Option<u64>, now: i64) -> Option<i64> {
+        days.map(|d| now - d as i64 * 86_400_000)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -68,6 +243,42 @@ fn default_redact_patterns() -> Vec<String> {
     vec![]
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_idle_gap_minutes() -> u64 {
+    30
+}
+
+fn default_embedding_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_embedding_batch() -> usize {
+    32
+}
+
+fn default_title_weight() -> f64 {
+    3.0
+}
+
+fn default_project_weight() -> f64 {
+    1.0
+}
+
+fn default_recency_boost() -> f64 {
+    0.5
+}
+
+fn default_recency_half_life() -> f64 {
+    30.0
+}
+
+fn default_named_boost() -> f64 {
+    1.5
+}
+
 fn default_model() -> String {
     "claude-sonnet-4-20250514".to_string()
 }
@@ -81,6 +292,38 @@ impl Default for PrivacyConfig {
         Self {
             ignore_patterns: default_ignore_patterns(),
             redact_patterns: default_redact_patterns(),
+            detect_credentials: true,
+            ignore_dirs: Vec::new(),
+        }
+    }
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            idle_gap_minutes: default_idle_gap_minutes(),
+        }
+    }
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            model: default_embedding_model(),
+            batch_size: default_embedding_batch(),
+        }
+    }
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            title_weight: default_title_weight(),
+            project_weight: default_project_weight(),
+            recency_boost: default_recency_boost(),
+            recency_half_life_days: default_recency_half_life(),
+            named_boost: default_named_boost(),
         }
     }
 }
@@ -99,15 +342,6 @@ impl Default for LlmConfig {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            privacy: PrivacyConfig::default(),
-            llm: LlmConfig::default(),
-        }
-    }
-}
-
 pub fn recall_dir() -> PathBuf {
     dirs::home_dir()
         .expect("Could not find home directory")
@@ -126,6 +360,16 @@ pub fn pause_file() -> PathBuf {
     recall_dir().join(".paused")
 }
 
+/// One marker per shell that has gone incognito, named by its session id.
+pub fn incognito_dir() -> PathBuf {
+    recall_dir().join("incognito")
+}
+
+/// Where `recall daemon` listens, when it is running.
+pub fn daemon_socket() -> PathBuf {
+    recall_dir().join("daemon.sock")
+}
+
 pub fn env_file() -> PathBuf {
     recall_dir().join("env")
 }
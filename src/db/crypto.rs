@@ -0,0 +1,222 @@
+//! Encryption at rest, for builds with the `encryption` feature (SQLCipher).
+//!
+//! The key never lives in config.toml. It comes from `RECALL_DB_KEY`, set in
+//! the environment or in `~/.recall/env`, or from the output of
+//! `[database] key_command`, so it can be kept in a keychain or password
+//! manager instead. With a key configured, a new database is created
+//! encrypted; an existing one is converted with `recall encrypt`.
+//!
+//! Whether a file is encrypted is read from its first bytes: a plaintext
+//! SQLite database always starts with the same header, which SQLCipher
+//! replaces with random salt.
+
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::config::settings::{daemon_socket, load_config};
+
+pub const KEY_ENV: &str = "RECALL_DB_KEY";
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether this binary was built with SQLCipher.
+pub const SUPPORTED: bool = cfg!(feature = "encryption");
+
+/// The database key, if one is configured.
+pub fn configured_key() -> Result<Option<String>> {
+    // Loading config also loads ~/.recall/env into the environment.
+    let config = load_config()?;
+    if let Ok(key) = std::env::var(KEY_ENV) {
+        if !key.is_empty() {
+            return Ok(Some(key));
+        }
+    }
+    match config.database.key_command.as_deref() {
+        Some(command) if !command.trim().is_empty() => run_key_command(command).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn run_key_command(command: &str) -> Result<String> {
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run [database] key_command `{}`", command))?;
+    if !output.status.success() {
+        bail!("[database] key_command `{}` failed ({})", command, output.status);
+    }
+    let key = String::from_utf8(output.stdout)
+        .context("[database] key_command printed something that is not text")?
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    if key.is_empty() {
+        bail!("[database] key_command `{}` printed an empty key", command);
+    }
+    Ok(key)
+}
+
+/// True for an existing file that is not a plaintext SQLite database.
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mut header = [0u8; 16];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header != SQLITE_HEADER),
+        // Empty or truncated: SQLite treats it as a new database.
+        Err(_) => Ok(false),
+    }
+}
+
+/// Apply `key` to a freshly opened connection, before anything else reads
+/// the file, and check that it opens the database.
+pub fn unlock(conn: &Connection, path: &Path, key: &str) -> Result<()> {
+    if !SUPPORTED {
+        bail!(
+            "{} is set, but this recall was built without encryption support; \
+             rebuild with `cargo install --features encryption`, or unset it",
+            KEY_ENV
+        );
+    }
+    conn.pragma_update(None, "key", key)?;
+    if conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(())).is_err() {
+        bail!(
+            "Could not decrypt {}: the key from {} or [database] key_command is wrong",
+            path.display(),
+            KEY_ENV
+        );
+    }
+    Ok(())
+}
+
+/// The error for an encrypted database opened without a key.
+pub fn missing_key(path: &Path) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{} is encrypted and no key is configured. Set {} in the environment or in \
+         ~/.recall/env, or set [database] key_command in config.toml",
+        path.display(),
+        KEY_ENV
+    )
+}
+
+/// Rewrite the plaintext database at `path` encrypted with `key`.
+pub fn encrypt(path: &Path, key: &str) -> Result<()> {
+    if is_encrypted(path)? {
+        bail!("{} is already encrypted", path.display());
+    }
+    convert(path, None, key)
+}
+
+/// Rewrite the encrypted database at `path` as plaintext.
+pub fn decrypt(path: &Path, key: &str) -> Result<()> {
+    if !is_encrypted(path)? {
+        bail!("{} is not encrypted", path.display());
+    }
+    convert(path, Some(key), "")
+}
+
+/// Copy the database into a new file with `to_key` (empty for plaintext)
+/// using `sqlcipher_export`, then swap it into place.
+fn convert(path: &Path, from_key: Option<&str>, to_key: &str) -> Result<()> {
+    if !SUPPORTED {
+        bail!("This recall was built without encryption support; rebuild with `cargo install --features encryption`");
+    }
+    if !path.exists() {
+        bail!("No database at {} yet", path.display());
+    }
+    // A daemon would keep writing to the file being replaced.
+    if std::os::unix::net::UnixStream::connect(daemon_socket()).is_ok() {
+        bail!("Stop `recall daemon` first, then run this again");
+    }
+
+    let target = sibling(path, "converting");
+    let _ = std::fs::remove_file(&target);
+
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    if let Some(key) = from_key {
+        unlock(&conn, path, key)?;
+    }
+    // Everything written so far has to be in the main file to be copied.
+    conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS converted KEY ?2",
+        rusqlite::params![target.to_string_lossy(), to_key],
+    )?;
+    conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()))
+        .context("Failed to copy the database")?;
+    conn.execute_batch(&format!("PRAGMA converted.user_version = {};", version))?;
+    conn.execute_batch("DETACH DATABASE converted;")?;
+    drop(conn);
+
+    std::fs::rename(&target, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    // The old WAL and shared-memory index belong to the file just replaced.
+    for suffix in ["wal", "shm"] {
+        let _ = std::fs::remove_file(sibling(path, suffix));
+    }
+    Ok(())
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!("-{}", suffix));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("recall-crypto-{}-{}.db", name, std::process::id()));
+        for p in [path.clone(), sibling(&path, "wal"), sibling(&path, "shm")] {
+            let _ = std::fs::remove_file(p);
+        }
+        path
+    }
+
+    #[test]
+    fn a_plaintext_database_is_recognised_by_its_header() {
+        let path = temp_db("plain");
+        assert!(!is_encrypted(&path).unwrap(), "a missing file is a new database");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER);")
+            .unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypting_and_decrypting_round_trips_the_data() {
+        let path = temp_db("round-trip");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch("CREATE TABLE t (x TEXT); INSERT INTO t VALUES ('hunter2');")
+            .unwrap();
+        drop(conn);
+
+        encrypt(&path, "correct horse").unwrap();
+        assert!(is_encrypted(&path).unwrap());
+        assert!(!std::fs::read(&path).unwrap().windows(7).any(|w| w == b"hunter2"));
+
+        let conn = Connection::open(&path).unwrap();
+        assert!(unlock(&conn, &path, "wrong").is_err());
+        let conn = Connection::open(&path).unwrap();
+        unlock(&conn, &path, "correct horse").unwrap();
+        let x: String = conn.query_row("SELECT x FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(x, "hunter2");
+        drop(conn);
+
+        decrypt(&path, "correct horse").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -1,3 +1,6 @@
+pub mod crypto;
 pub mod models;
 pub mod queries;
+pub mod retention;
 pub mod schema;
+pub mod work_sessions;
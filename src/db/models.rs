@@ -31,6 +31,22 @@ pub struct Summary {
     pub tags: Option<String>,
     pub intent: Option<String>,
     pub created_at: i64,
+    /// The work session summarized, by its first command and when its last
+    /// one finished. Summaries written before sessions were split cover the
+    /// whole shell session and leave these empty.
+    pub started_at: Option<i64>,
+    pub ended_at: Option<i64>,
+}
+
+impl Summary {
+    /// The tags, which the model writes as a JSON array. Anything else
+    /// counts as none.
+    pub fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .as_deref()
+            .and_then(|tags| serde_json::from_str::<Vec<String>>(tags).ok())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
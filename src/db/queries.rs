@@ -1,11 +1,21 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, OptionalExtension};
 
 use super::models::{Command, SearchResult, Session, Summary, SummarySearchResult};
+use crate::search::query::Query;
 
+/// Record a session the first time one of its commands arrives. Commands are
+/// logged in the background and can land after the shell has already exited,
+/// so a row created by `end_session` is filled in rather than left alone.
 pub fn insert_session(conn: &Connection, session: &Session) -> Result<()> {
     conn.execute(
-        "INSERT OR IGNORE INTO sessions (id, start_time, end_time, terminal_app, initial_dir) VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO sessions (id, start_time, end_time, terminal_app, initial_dir) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(id) DO UPDATE SET
+             initial_dir = CASE WHEN excluded.start_time < sessions.start_time
+                                THEN excluded.initial_dir
+                                ELSE COALESCE(sessions.initial_dir, excluded.initial_dir) END,
+             start_time = MIN(sessions.start_time, excluded.start_time),
+             terminal_app = COALESCE(sessions.terminal_app, excluded.terminal_app)",
         params![session.id, session.start_time, session.end_time, session.terminal_app, session.initial_dir],
     ).context("Failed to insert session")?;
     Ok(())
@@ -31,23 +41,95 @@ pub fn insert_command(conn: &Connection, cmd: &Command) -> Result<i64> {
     Ok(conn.last_insert_rowid())
 }
 
-pub fn search_commands(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-    // Wrap in double quotes so FTS5 treats special chars (-, /, .) as literals
-    // instead of operators (e.g. "msp-cli" won't become "msp NOT cli")
-    let escaped = format!("\"{}\"", query.replace('"', "\"\""));
+/// Commands matching a parsed search: best FTS5 match first when there is
+/// text, newest first when there are only filters. Every filter is part of
+/// the query, so a page is always full while there are more matches.
+pub fn find_commands(
+    conn: &Connection,
+    query: &Query,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<SearchResult>> {
+    let (where_sql, mut values) = query.command_clauses("c");
+    let sql = match query.match_expr() {
+        Some(match_expr) => {
+            values.insert(0, SqlValue::Text(match_expr));
+            format!(
+                "SELECT c.id, c.session_id, c.command_text, c.timestamp, c.duration_ms, c.cwd,
+                        c.git_repo, c.git_branch, c.exit_code, c.output, rank
+                 FROM commands_fts f
+                 JOIN commands c ON c.id = f.rowid
+                 WHERE commands_fts MATCH ?{}
+                 ORDER BY rank, c.id DESC
+                 LIMIT ? OFFSET ?",
+                where_sql
+            )
+        }
+        None => format!(
+            "SELECT c.id, c.session_id, c.command_text, c.timestamp, c.duration_ms, c.cwd,
+                    c.git_repo, c.git_branch, c.exit_code, c.output, 0.0
+             FROM commands c
+             WHERE 1=1{}
+             ORDER BY c.timestamp DESC, c.id DESC
+             LIMIT ? OFFSET ?",
+            where_sql
+        ),
+    };
+    values.push(SqlValue::Integer(limit as i64));
+    values.push(SqlValue::Integer(offset as i64));
+    search_results(conn, &sql, values)
+}
 
-    let mut stmt = conn.prepare(
-        "SELECT c.id, c.session_id, c.command_text, c.timestamp, c.duration_ms, c.cwd,
-                c.git_repo, c.git_branch, c.exit_code, c.output, rank
-         FROM commands_fts f
-         JOIN commands c ON c.id = f.rowid
-         WHERE commands_fts MATCH ?1
-         ORDER BY rank
-         LIMIT ?2",
-    )?;
+/// Commands containing the query's text anywhere, mid-word included,
+/// ranked by BM25 over the trigram index. Text under three characters is
+/// matched by a scan instead.
+pub fn find_commands_fuzzy(
+    conn: &Connection,
+    query: &Query,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<SearchResult>> {
+    let (where_sql, mut values) = query.command_clauses("c");
+    let sql = match query.substring_expr() {
+        Some(match_expr) => {
+            values.insert(0, SqlValue::Text(match_expr));
+            format!(
+                "SELECT c.id, c.session_id, c.command_text, c.timestamp, c.duration_ms, c.cwd,
+                        c.git_repo, c.git_branch, c.exit_code, c.output, rank
+                 FROM commands_trigram t
+                 JOIN commands c ON c.id = t.rowid
+                 WHERE commands_trigram MATCH ?{}
+                 ORDER BY rank, c.id DESC
+                 LIMIT ? OFFSET ?",
+                where_sql
+            )
+        }
+        None => {
+            let text = query.text();
+            if text.trim().is_empty() {
+                return find_commands(conn, query, limit, offset);
+            }
+            values.insert(0, SqlValue::Text(text.trim().to_string()));
+            format!(
+                "SELECT c.id, c.session_id, c.command_text, c.timestamp, c.duration_ms, c.cwd,
+                        c.git_repo, c.git_branch, c.exit_code, c.output, 0.0
+                 FROM commands c
+                 WHERE instr(lower(c.command_text), lower(?)) > 0{}
+                 ORDER BY c.timestamp DESC, c.id DESC
+                 LIMIT ? OFFSET ?",
+                where_sql
+            )
+        }
+    };
+    values.push(SqlValue::Integer(limit as i64));
+    values.push(SqlValue::Integer(offset as i64));
+    search_results(conn, &sql, values)
+}
 
+fn search_results(conn: &Connection, sql: &str, values: Vec<SqlValue>) -> Result<Vec<SearchResult>> {
+    let mut stmt = conn.prepare(sql)?;
     let results = stmt
-        .query_map(params![escaped, limit as i64], |row| {
+        .query_map(params_from_iter(values), |row| {
             Ok(SearchResult {
                 command: Command {
                     id: Some(row.get(0)?),
@@ -70,24 +152,38 @@ pub fn search_commands(conn: &Connection, query: &str, limit: usize) -> Result<V
     Ok(results)
 }
 
-pub fn search_summaries(
-    conn: &Connection,
-    query: &str,
-    limit: usize,
-) -> Result<Vec<SummarySearchResult>> {
-    let escaped = format!("\"{}\"", query.replace('"', "\"\""));
-
-    let mut stmt = conn.prepare(
-        "SELECT s.id, s.session_id, s.summary_text, s.tags, s.intent, s.created_at, rank
-         FROM summaries_fts f
-         JOIN summaries s ON s.id = f.rowid
-         WHERE summaries_fts MATCH ?1
-         ORDER BY rank
-         LIMIT ?2",
-    )?;
-
+/// Summaries matching a parsed search, like `find_commands`.
+pub fn find_summaries(conn: &Connection, query: &Query, limit: usize) -> Result<Vec<SummarySearchResult>> {
+    let (where_sql, mut values) = query.summary_clauses("s");
+    let sql = match query.match_expr() {
+        Some(match_expr) => {
+            values.insert(0, SqlValue::Text(match_expr));
+            format!(
+                "SELECT s.id, s.session_id, s.summary_text, s.tags, s.intent, s.created_at,
+                        s.started_at, s.ended_at, rank
+                 FROM summaries_fts f
+                 JOIN summaries s ON s.id = f.rowid
+                 WHERE summaries_fts MATCH ?{}
+                 ORDER BY rank
+                 LIMIT ?",
+                where_sql
+            )
+        }
+        None => format!(
+            "SELECT s.id, s.session_id, s.summary_text, s.tags, s.intent, s.created_at,
+                    s.started_at, s.ended_at, 0.0
+             FROM summaries s
+             WHERE 1=1{}
+             ORDER BY COALESCE(s.ended_at, s.created_at) DESC
+             LIMIT ?",
+            where_sql
+        ),
+    };
+    values.push(SqlValue::Integer(limit as i64));
+
+    let mut stmt = conn.prepare(&sql)?;
     let results = stmt
-        .query_map(params![escaped, limit as i64], |row| {
+        .query_map(params_from_iter(values), |row| {
             Ok(SummarySearchResult {
                 summary: Summary {
                     id: Some(row.get(0)?),
@@ -96,8 +192,10 @@ pub fn search_summaries(
                     tags: row.get(3)?,
                     intent: row.get(4)?,
                     created_at: row.get(5)?,
+                    started_at: row.get(6)?,
+                    ended_at: row.get(7)?,
                 },
-                rank: row.get(6)?,
+                rank: row.get(8)?,
             })
         })?
         .collect::<std::result::Result<Vec<_>, _>>()
@@ -106,62 +204,8 @@ pub fn search_summaries(
     Ok(results)
 }
 
-pub fn get_commands_today(conn: &Connection) -> Result<Vec<Command>> {
-    let today_start = chrono::Local::now()
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_local_timezone(chrono::Local)
-        .unwrap()
-        .timestamp_millis();
-
-    let mut stmt = conn.prepare(
-        "SELECT id, session_id, command_text, timestamp, duration_ms, cwd, git_repo, git_branch, exit_code, output
-         FROM commands
-         WHERE timestamp >= ?1
-         ORDER BY timestamp ASC",
-    )?;
-
-    let results = stmt
-        .query_map(params![today_start], |row| {
-            Ok(Command {
-                id: Some(row.get(0)?),
-                session_id: row.get(1)?,
-                command_text: row.get(2)?,
-                timestamp: row.get(3)?,
-                duration_ms: row.get(4)?,
-                cwd: row.get(5)?,
-                git_repo: row.get(6)?,
-                git_branch: row.get(7)?,
-                exit_code: row.get(8)?,
-                output: row.get(9)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()
-        .context("Failed to get today's commands")?;
-
-    Ok(results)
-}
-
-pub fn get_commands_on_date(conn: &Connection, date: &str) -> Result<Vec<Command>> {
-    let parsed = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
-        .with_context(|| format!("Invalid date format: {date}. Use YYYY-MM-DD"))?;
-
-    let start = parsed
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_local_timezone(chrono::Local)
-        .unwrap()
-        .timestamp_millis();
-    let end = parsed
-        .succ_opt()
-        .unwrap()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_local_timezone(chrono::Local)
-        .unwrap()
-        .timestamp_millis();
-
+/// Commands run from `start` up to `end` (epoch milliseconds), oldest first.
+pub fn get_commands_between(conn: &Connection, start: i64, end: i64) -> Result<Vec<Command>> {
     let mut stmt = conn.prepare(
         "SELECT id, session_id, command_text, timestamp, duration_ms, cwd, git_repo, git_branch, exit_code, output
          FROM commands
@@ -185,7 +229,7 @@ pub fn get_commands_on_date(conn: &Connection, date: &str) -> Result<Vec<Command
             })
         })?
         .collect::<std::result::Result<Vec<_>, _>>()
-        .context("Failed to get commands for date")?;
+        .context("Failed to get commands")?;
 
     Ok(results)
 }
@@ -225,9 +269,13 @@ pub fn get_sessions(
     offset: usize,
 ) -> Result<Vec<Session>> {
     let mut stmt = conn.prepare(
+        // Most recently active first: a terminal opened days ago may be where
+        // the latest work happened.
         "SELECT id, start_time, end_time, terminal_app, initial_dir
          FROM sessions
-         ORDER BY start_time DESC
+         ORDER BY COALESCE(
+             (SELECT MAX(c.timestamp) FROM commands c WHERE c.session_id = sessions.id),
+             start_time) DESC
          LIMIT ?1 OFFSET ?2",
     )?;
 
@@ -249,28 +297,37 @@ pub fn get_sessions(
 
 pub fn insert_summary(conn: &Connection, summary: &Summary) -> Result<i64> {
     conn.execute(
-        "INSERT INTO summaries (session_id, summary_text, tags, intent, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO summaries (session_id, summary_text, tags, intent, created_at, started_at, ended_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             summary.session_id,
             summary.summary_text,
             summary.tags,
             summary.intent,
             summary.created_at,
+            summary.started_at,
+            summary.ended_at,
         ],
     )
     .context("Failed to insert summary")?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Sessions with at least `min_commands` commands newer than anything already
+/// summarized. A session summarized whole, before sessions were split into
+/// work sessions, is done.
 pub fn get_unsummarized_sessions(conn: &Connection, min_commands: usize) -> Result<Vec<String>> {
     let mut stmt = conn.prepare(
         "SELECT s.id
          FROM sessions s
-         LEFT JOIN summaries su ON su.session_id = s.id
-         WHERE su.id IS NULL
-         GROUP BY s.id
-         HAVING (SELECT COUNT(*) FROM commands c WHERE c.session_id = s.id) >= ?1",
+         WHERE NOT EXISTS (
+             SELECT 1 FROM summaries su WHERE su.session_id = s.id AND su.ended_at IS NULL
+         )
+         AND (SELECT COUNT(*) FROM commands c
+              WHERE c.session_id = s.id
+                AND c.timestamp > COALESCE(
+                    (SELECT MAX(su.ended_at) FROM summaries su WHERE su.session_id = s.id), -1)
+             ) >= ?1",
     )?;
 
     let results = stmt
@@ -281,6 +338,105 @@ pub fn get_unsummarized_sessions(conn: &Connection, min_commands: usize) -> Resu
     Ok(results)
 }
 
+/// When the latest summarized work session of a session finished, if any.
+pub fn last_summarized_at(conn: &Connection, session_id: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT MAX(ended_at) FROM summaries WHERE session_id = ?1",
+        params![session_id],
+        |row| row.get(0),
+    )
+    .context("Failed to read summary coverage")
+}
+
+/// The newest summary covering any of `start`..=`end` in a shell session.
+/// A summary written before sessions were split covers all of it.
+pub fn summary_for(conn: &Connection, session_id: &str, start: i64, end: i64) -> Result<Option<Summary>> {
+    conn.query_row(
+        "SELECT id, session_id, summary_text, tags, intent, created_at, started_at, ended_at
+         FROM summaries
+         WHERE session_id = ?1
+           AND (started_at IS NULL OR (started_at <= ?3 AND ended_at >= ?2))
+         ORDER BY COALESCE(ended_at, created_at) DESC
+         LIMIT 1",
+        params![session_id, start, end],
+        |row| {
+            Ok(Summary {
+                id: Some(row.get(0)?),
+                session_id: row.get(1)?,
+                summary_text: row.get(2)?,
+                tags: row.get(3)?,
+                intent: row.get(4)?,
+                created_at: row.get(5)?,
+                started_at: row.get(6)?,
+                ended_at: row.get(7)?,
+            })
+        },
+    )
+    .optional()
+    .context("Failed to read the session's summary")
+}
+
+/// Every tag summaries carry, with how many carry it, most used first.
+pub fn summary_tags(conn: &Connection) -> Result<Vec<(String, usize)>> {
+    label_counts(
+        conn,
+        "SELECT lower(t.value), COUNT(*) FROM summaries s,
+                json_each(CASE WHEN json_valid(s.tags) THEN s.tags ELSE '[]' END) t
+         GROUP BY lower(t.value)
+         ORDER BY COUNT(*) DESC, lower(t.value)",
+    )
+}
+
+/// Every intent summaries were given, with how many, most used first.
+pub fn summary_intents(conn: &Connection) -> Result<Vec<(String, usize)>> {
+    label_counts(
+        conn,
+        "SELECT lower(intent), COUNT(*) FROM summaries
+         WHERE intent IS NOT NULL AND intent != '' AND intent != 'unknown'
+         GROUP BY lower(intent)
+         ORDER BY COUNT(*) DESC, lower(intent)",
+    )
+}
+
+fn label_counts(conn: &Connection, sql: &str) -> Result<Vec<(String, usize)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?;
+    rows.collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to count summary labels")
+}
+
+pub fn get_session(conn: &Connection, session_id: &str) -> Result<Option<Session>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, start_time, end_time, terminal_app, initial_dir
+         FROM sessions
+         WHERE id = ?1",
+    )?;
+    let mut rows = stmt.query_map(params![session_id], |row| {
+        Ok(Session {
+            id: row.get(0)?,
+            start_time: row.get(1)?,
+            end_time: row.get(2)?,
+            terminal_app: row.get(3)?,
+            initial_dir: row.get(4)?,
+        })
+    })?;
+    rows.next()
+        .transpose()
+        .context("Failed to get session")
+}
+
+/// Stamp the moment a shell exited. The session's last command may still be
+/// on its way, so the row is created here if it does not exist yet.
+pub fn end_session(conn: &Connection, session_id: &str, end_time: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO sessions (id, start_time, end_time) VALUES (?1, ?2, ?2)
+         ON CONFLICT(id) DO UPDATE SET end_time = excluded.end_time",
+        params![session_id, end_time],
+    )
+    .context("Failed to end session")?;
+    Ok(())
+}
+
 pub fn get_all_commands(conn: &Connection, limit: usize) -> Result<Vec<Command>> {
     let mut stmt = conn.prepare(
         "SELECT id, session_id, command_text, timestamp, duration_ms, cwd, git_repo, git_branch, exit_code, output
@@ -309,3 +465,430 @@ pub fn get_all_commands(conn: &Connection, limit: usize) -> Result<Vec<Command>>
 
     Ok(results)
 }
+
+/// Which commands to pick out of the history. Every field that is set must
+/// match; a selection with nothing set matches nothing.
+#[derive(Debug, Default, Clone)]
+pub struct CommandSelection {
+    /// Substring of the command text or its output
+    pub text: Option<String>,
+    pub ids: Vec<i64>,
+    pub session_id: Option<String>,
+    /// Epoch milliseconds, inclusive
+    pub since: Option<i64>,
+    /// Epoch milliseconds, exclusive
+    pub until: Option<i64>,
+}
+
+impl CommandSelection {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.ids.is_empty()
+            && self.session_id.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+}
+
+pub fn select_commands(conn: &Connection, selection: &CommandSelection) -> Result<Vec<Command>> {
+    if selection.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut sql = String::from(
+        "SELECT id, session_id, command_text, timestamp, duration_ms, cwd, git_repo, git_branch, exit_code, output
+         FROM commands WHERE 1 = 1",
+    );
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+    if let Some(text) = &selection.text {
+        // instr rather than LIKE: `%` and `_` in a secret are literal.
+        sql.push_str(" AND (instr(command_text, ?) > 0 OR instr(COALESCE(output, ''), ?) > 0)");
+        values.push(text.clone().into());
+        values.push(text.clone().into());
+    }
+    if !selection.ids.is_empty() {
+        let marks = vec!["?"; selection.ids.len()].join(", ");
+        sql.push_str(&format!(" AND id IN ({})", marks));
+        values.extend(selection.ids.iter().map(|&id| id.into()));
+    }
+    if let Some(session) = &selection.session_id {
+        sql.push_str(" AND session_id = ?");
+        values.push(session.clone().into());
+    }
+    if let Some(since) = selection.since {
+        sql.push_str(" AND timestamp >= ?");
+        values.push(since.into());
+    }
+    if let Some(until) = selection.until {
+        sql.push_str(" AND timestamp < ?");
+        values.push(until.into());
+    }
+    sql.push_str(" ORDER BY timestamp");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let results = stmt
+        .query_map(params_from_iter(values), |row| {
+            Ok(Command {
+                id: Some(row.get(0)?),
+                session_id: row.get(1)?,
+                command_text: row.get(2)?,
+                timestamp: row.get(3)?,
+                duration_ms: row.get(4)?,
+                cwd: row.get(5)?,
+                git_repo: row.get(6)?,
+                git_branch: row.get(7)?,
+                exit_code: row.get(8)?,
+                output: row.get(9)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to select commands")?;
+
+    Ok(results)
+}
+
+/// Session ids starting with `prefix`, so a session can be named by the
+/// first few characters shown in listings.
+pub fn find_session_ids(conn: &Connection, prefix: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT id FROM sessions WHERE substr(id, 1, length(?1)) = ?1")?;
+    let ids = stmt
+        .query_map(params![prefix], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()
+        .context("Failed to look up sessions")?;
+    Ok(ids)
+}
+
+/// What `delete_commands` removed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Deleted {
+    pub commands: usize,
+    pub summaries: usize,
+    pub sessions: usize,
+}
+
+/// Delete commands for good, along with every summary that described them
+/// and any session left with nothing in it. The FTS triggers take the rows
+/// out of the search indexes in the same transaction.
+pub fn delete_commands(conn: &Connection, commands: &[Command]) -> Result<Deleted> {
+    let tx = conn.unchecked_transaction()?;
+    let mut deleted = Deleted::default();
+    let mut sessions: Vec<&str> = Vec::new();
+
+    for cmd in commands {
+        let Some(id) = cmd.id else { continue };
+        // A summary covers its work session's span; one from before spans
+        // were recorded covers the whole session.
+        deleted.summaries += tx.execute(
+            "DELETE FROM summaries WHERE session_id = ?1
+             AND (started_at IS NULL OR ?2 BETWEEN started_at AND ended_at)",
+            params![cmd.session_id, cmd.timestamp],
+        )?;
+        deleted.commands += tx.execute("DELETE FROM commands WHERE id = ?1", params![id])?;
+        if !sessions.contains(&cmd.session_id.as_str()) {
+            sessions.push(&cmd.session_id);
+        }
+    }
+
+    for session in sessions {
+        deleted.summaries += tx.execute(
+            "DELETE FROM summaries WHERE session_id = ?1
+             AND NOT EXISTS (SELECT 1 FROM commands WHERE session_id = ?1)",
+            params![session],
+        )?;
+        deleted.sessions += tx.execute(
+            "DELETE FROM sessions WHERE id = ?1
+             AND NOT EXISTS (SELECT 1 FROM commands WHERE session_id = ?1)",
+            params![session],
+        )?;
+    }
+
+    tx.commit().context("Failed to delete commands")?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::initialize_db;
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_db(&conn).unwrap();
+        conn
+    }
+
+    fn add_commands(conn: &Connection, session: &str, times: &[i64]) {
+        insert_session(
+            conn,
+            &Session {
+                id: session.into(),
+                start_time: times[0],
+                end_time: None,
+                terminal_app: None,
+                initial_dir: None,
+            },
+        )
+        .unwrap();
+        for &at in times {
+            insert_command(
+                conn,
+                &Command {
+                    id: None,
+                    session_id: session.into(),
+                    command_text: "make".into(),
+                    timestamp: at,
+                    duration_ms: Some(10),
+                    cwd: None,
+                    git_repo: None,
+                    git_branch: None,
+                    exit_code: Some(0),
+                    output: None,
+                },
+            )
+            .unwrap();
+        }
+    }
+
+    fn summary(session: &str, span: Option<(i64, i64)>) -> Summary {
+        Summary {
+            id: None,
+            session_id: session.into(),
+            summary_text: "built things".into(),
+            tags: None,
+            intent: None,
+            created_at: 0,
+            started_at: span.map(|s| s.0),
+            ended_at: span.map(|s| s.1),
+        }
+    }
+
+    #[test]
+    fn structured_filters_and_exclusions_narrow_command_search() {
+        let conn = db();
+        add_commands(&conn, "s", &[100]);
+        let run = |text: &str, repo: &str, exit: i32, duration: i64| {
+            insert_command(
+                &conn,
+                &Command {
+                    id: None,
+                    session_id: "s".into(),
+                    command_text: text.into(),
+                    timestamp: 200,
+                    duration_ms: Some(duration),
+                    cwd: Some(format!("/src/{}", repo)),
+                    git_repo: Some(repo.into()),
+                    git_branch: Some("main".into()),
+                    exit_code: Some(exit),
+                    output: None,
+                },
+            )
+            .unwrap();
+        };
+        run("cargo test --release", "api", 101, 90_000);
+        run("cargo test", "api", 0, 4_000);
+        run("cargo test", "web", 1, 2_000);
+
+        let find = |input: &str| {
+            let query = crate::search::query::parse(input).unwrap();
+            find_commands(&conn, &query, 10, 0).unwrap().len()
+        };
+        assert_eq!(find("cargo test"), 3);
+        assert_eq!(find("cargo repo:api"), 2);
+        assert_eq!(find("cargo exit:!0"), 2);
+        assert_eq!(find("cargo took:>30s"), 1);
+        assert_eq!(find("cargo dir:/src/web"), 1);
+        assert_eq!(find("cargo -release"), 2);
+        // Filters alone list newest first, including the plain `make`.
+        assert_eq!(find("branch:main"), 3);
+        assert_eq!(find("exit:0"), 2);
+        assert_eq!(find("cargo model:opus"), 0, "agent-only keys rule commands out");
+    }
+
+    #[test]
+    fn fuzzy_command_search_matches_inside_words() {
+        let conn = db();
+        add_commands(&conn, "s", &[1, 2]);
+        conn.execute_batch(
+            "UPDATE commands SET command_text = 'docker compose up -d' WHERE timestamp = 1;
+             UPDATE commands SET command_text = 'kubectl apply -f deploy.yaml' WHERE timestamp = 2;",
+        )
+        .unwrap();
+        let texts = |q: &str| {
+            find_commands_fuzzy(&conn, &crate::search::query::parse(q).unwrap(), 10, 0)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.command.command_text)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(find_commands(&conn, &crate::search::query::parse("ompos").unwrap(), 10, 0)
+            .unwrap()
+            .is_empty());
+        assert_eq!(texts("ompos"), vec!["docker compose up -d"]);
+        assert_eq!(texts("PLOY.Y"), vec!["kubectl apply -f deploy.yaml"]);
+        assert_eq!(texts("UP"), vec!["docker compose up -d"]);
+        assert!(texts("ompos repo:elsewhere").is_empty());
+    }
+
+    #[test]
+    fn a_session_with_new_work_after_its_last_summary_is_summarized_again() {
+        let conn = db();
+        add_commands(&conn, "s", &[100, 200, 300, 10_000, 10_100, 10_200]);
+        insert_summary(&conn, &summary("s", Some((100, 310)))).unwrap();
+
+        assert_eq!(get_unsummarized_sessions(&conn, 3).unwrap(), vec!["s"]);
+        assert_eq!(last_summarized_at(&conn, "s").unwrap(), Some(310));
+
+        insert_summary(&conn, &summary("s", Some((10_000, 10_210)))).unwrap();
+        assert!(get_unsummarized_sessions(&conn, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_whole_session_summary_from_before_splitting_counts_as_done() {
+        let conn = db();
+        add_commands(&conn, "old", &[1, 2, 3]);
+        insert_summary(&conn, &summary("old", None)).unwrap();
+        assert!(get_unsummarized_sessions(&conn, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn each_work_session_finds_its_own_summary() {
+        let conn = db();
+        add_commands(&conn, "s", &[100, 200, 10_000, 10_100]);
+        let mut morning = summary("s", Some((100, 210)));
+        morning.summary_text = "morning".into();
+        let mut evening = summary("s", Some((10_000, 10_110)));
+        evening.summary_text = "evening".into();
+        insert_summary(&conn, &morning).unwrap();
+        insert_summary(&conn, &evening).unwrap();
+
+        let text = |start, end| summary_for(&conn, "s", start, end).unwrap().map(|s| s.summary_text);
+        assert_eq!(text(100, 200).as_deref(), Some("morning"));
+        assert_eq!(text(10_000, 10_100).as_deref(), Some("evening"));
+        assert_eq!(text(5_000, 6_000), None);
+
+        // A whole-session summary from before splitting covers any part.
+        add_commands(&conn, "old", &[1]);
+        insert_summary(&conn, &summary("old", None)).unwrap();
+        assert!(summary_for(&conn, "old", 5_000, 6_000).unwrap().is_some());
+    }
+
+    #[test]
+    fn summaries_filter_and_count_by_tag_and_intent() {
+        let conn = db();
+        add_commands(&conn, "s", &[1]);
+        for (tags, intent) in [
+            (r#"["rust", "Debugging"]"#, "debugging"),
+            (r#"["rust"]"#, "development"),
+            ("not json", "Debugging"),
+        ] {
+            let mut tagged = summary("s", None);
+            tagged.tags = Some(tags.into());
+            tagged.intent = Some(intent.into());
+            insert_summary(&conn, &tagged).unwrap();
+        }
+
+        let count = |input: &str| {
+            let query = crate::search::query::parse(input).unwrap();
+            find_summaries(&conn, &query, 10).unwrap().len()
+        };
+        assert_eq!(count("tag:rust"), 2);
+        assert_eq!(count("tag:debugging"), 1, "any case, and bad JSON has no tags");
+        assert_eq!(count("intent:debugging"), 2);
+        assert_eq!(count("tag:rust intent:development"), 1);
+
+        assert_eq!(
+            summary_tags(&conn).unwrap(),
+            vec![("rust".to_string(), 2), ("debugging".to_string(), 1)]
+        );
+        assert_eq!(
+            summary_intents(&conn).unwrap(),
+            vec![("debugging".to_string(), 2), ("development".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn ending_a_session_stamps_its_end_time() {
+        let conn = db();
+        add_commands(&conn, "s", &[100]);
+        end_session(&conn, "s", 5_000).unwrap();
+        assert_eq!(get_session(&conn, "s").unwrap().unwrap().end_time, Some(5_000));
+        assert!(get_session(&conn, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn a_command_logged_after_its_shell_exited_keeps_the_end_time() {
+        let conn = db();
+        end_session(&conn, "s", 5_000).unwrap();
+        add_commands(&conn, "s", &[100]);
+        let session = get_session(&conn, "s").unwrap().unwrap();
+        assert_eq!(session.start_time, 100);
+        assert_eq!(session.end_time, Some(5_000));
+    }
+
+    fn fts_hits(conn: &Connection, table: &str, term: &str) -> i64 {
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM {0} WHERE {0} MATCH ?1", table),
+            params![term],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn forgetting_a_command_takes_it_out_of_search_and_its_summary() {
+        let conn = db();
+        add_commands(&conn, "s", &[100, 200]);
+        add_commands(&conn, "t", &[50_000]);
+        conn.execute("UPDATE commands SET command_text = 'login hunter2' WHERE timestamp = 200", [])
+            .unwrap();
+        insert_summary(&conn, &summary("s", Some((100, 210)))).unwrap();
+        assert_eq!(fts_hits(&conn, "commands_fts", "hunter2"), 1);
+        assert_eq!(fts_hits(&conn, "summaries_fts", "built"), 1);
+
+        let selection = CommandSelection {
+            text: Some("hunter2".into()),
+            ..CommandSelection::default()
+        };
+        let matched = select_commands(&conn, &selection).unwrap();
+        assert_eq!(matched.len(), 1);
+
+        let deleted = delete_commands(&conn, &matched).unwrap();
+        assert_eq!(deleted, Deleted { commands: 1, summaries: 1, sessions: 0 });
+        assert_eq!(fts_hits(&conn, "commands_fts", "hunter2"), 0);
+        assert_eq!(fts_hits(&conn, "summaries_fts", "built"), 0);
+        assert_eq!(get_session_commands(&conn, "s").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn forgetting_a_whole_session_removes_the_session() {
+        let conn = db();
+        add_commands(&conn, "s", &[100, 200]);
+        let selection = CommandSelection {
+            session_id: Some("s".into()),
+            ..CommandSelection::default()
+        };
+        let matched = select_commands(&conn, &selection).unwrap();
+        let deleted = delete_commands(&conn, &matched).unwrap();
+        assert_eq!(deleted.sessions, 1);
+        assert!(get_session(&conn, "s").unwrap().is_none());
+        assert!(select_commands(&conn, &CommandSelection::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn adding_the_delete_triggers_heals_rows_deleted_by_hand() {
+        let conn = db();
+        add_commands(&conn, "s", &[100]);
+        // A database from before the triggers, where a row was deleted by hand.
+        conn.execute_batch(
+            "DROP TRIGGER commands_ad; DROP TRIGGER commands_au;
+             DELETE FROM commands;",
+        )
+        .unwrap();
+        assert_eq!(fts_hits(&conn, "commands_fts", "make"), 1);
+
+        initialize_db(&conn).unwrap();
+        assert_eq!(fts_hits(&conn, "commands_fts", "make"), 0);
+    }
+}
@@ -0,0 +1,347 @@
+//! Retention: applying `[retention]` limits to the database.
+//!
+//! Output is the bulk of shell history (up to 10 KB a command) and the least
+//! valuable part of it after a few weeks, so it goes first; the commands
+//! themselves go later, and summaries are kept for as long as their session
+//! has anything left. Deleted rows only free pages inside the file: the
+//! caller decides when the `optimize` and `VACUUM` that shrink it are worth
+//! the wait.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::config::settings::RetentionConfig;
+use crate::privacy::filter;
+
+/// How much of what is left the size cap trims per round, before measuring
+/// again.
+const CAP_STEP_PERCENT: i64 = 10;
+const CAP_MIN_STEP: i64 = 100;
+
+/// What a clean-up removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reclaimed {
+    pub outputs: usize,
+    pub commands: usize,
+    pub sessions: usize,
+    pub agent_chunks: usize,
+    /// Set when the size cap could not be met without deleting kept commands.
+    pub over_cap: bool,
+}
+
+impl Reclaimed {
+    pub fn is_empty(&self) -> bool {
+        self.outputs == 0 && self.commands == 0 && self.sessions == 0 && self.agent_chunks == 0
+    }
+}
+
+/// Apply every configured limit.
+pub fn apply(conn: &Connection, policy: &RetentionConfig, now: i64) -> Result<Reclaimed> {
+    let mut reclaimed = Reclaimed::default();
+
+    if let Some(cutoff) = RetentionConfig::cutoff(policy.output_days, now) {
+        reclaimed.outputs += drop_output_before(conn, cutoff, i64::MAX)?;
+    }
+    if let Some(cutoff) = RetentionConfig::cutoff(policy.command_days, now) {
+        reclaimed.commands +=
+            delete_commands_before(conn, cutoff, &policy.keep_patterns, usize::MAX)?;
+    }
+    if let Some(cutoff) = RetentionConfig::cutoff(policy.agent_days, now) {
+        reclaimed.agent_chunks += drop_agent_text_before(conn, cutoff)?;
+    }
+    if let Some(mb) = policy.max_db_mb {
+        enforce_cap(conn, mb as i64 * 1024 * 1024, &policy.keep_patterns, &mut reclaimed)?;
+    }
+
+    reclaimed.sessions += delete_empty_sessions(conn)?;
+    Ok(reclaimed)
+}
+
+/// Forget the output of commands that ran before `cutoff`, at most `limit`
+/// of them, oldest first.
+fn drop_output_before(conn: &Connection, cutoff: i64, limit: i64) -> Result<usize> {
+    let dropped = conn
+        .execute(
+            "UPDATE commands SET output = NULL WHERE id IN (
+                SELECT id FROM commands
+                WHERE output IS NOT NULL AND timestamp < ?1
+                ORDER BY timestamp LIMIT ?2
+             )",
+            params![cutoff, limit],
+        )
+        .context("Failed to drop old command output")?;
+    Ok(dropped)
+}
+
+/// Delete up to `limit` commands that ran before `cutoff`, oldest first,
+/// sparing any that match `keep`.
+fn delete_commands_before(
+    conn: &Connection,
+    cutoff: i64,
+    keep: &[String],
+    limit: usize,
+) -> Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT id, command_text FROM commands WHERE timestamp < ?1 ORDER BY timestamp",
+    )?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![cutoff], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .filter_map(|row| row.ok())
+        .filter(|(_, text)| !filter::matches_any(text, keep))
+        .map(|(id, _)| id)
+        .take(limit)
+        .collect();
+
+    let tx = conn.unchecked_transaction()?;
+    for id in &ids {
+        tx.execute("DELETE FROM commands WHERE id = ?1", params![id])?;
+    }
+    tx.commit().context("Failed to delete old commands")?;
+    Ok(ids.len())
+}
+
+/// Drop the indexed text of agent sessions last active before `cutoff`. The
+/// session rows stay, so they are still listed and the indexer does not
+/// bring the text back while the transcript is unchanged.
+fn drop_agent_text_before(conn: &Connection, cutoff: i64) -> Result<usize> {
+    let dropped = conn
+        .execute(
+            "DELETE FROM ai_chunks WHERE session_uid IN (
+                SELECT uid FROM ai_sessions WHERE last_activity < ?1
+             )",
+            params![cutoff],
+        )
+        .context("Failed to drop old agent session text")?;
+    Ok(dropped)
+}
+
+/// Shell sessions with no commands and nothing summarized about them.
+fn delete_empty_sessions(conn: &Connection) -> Result<usize> {
+    let deleted = conn
+        .execute(
+            "DELETE FROM sessions
+             WHERE NOT EXISTS (SELECT 1 FROM commands WHERE session_id = sessions.id)
+               AND NOT EXISTS (SELECT 1 FROM summaries WHERE session_id = sessions.id)",
+            [],
+        )
+        .context("Failed to delete empty sessions")?;
+    Ok(deleted)
+}
+
+/// Trim the oldest history, a slice at a time, until the live pages fit in
+/// `cap` bytes: output first, then commands.
+fn enforce_cap(
+    conn: &Connection,
+    cap: i64,
+    keep: &[String],
+    reclaimed: &mut Reclaimed,
+) -> Result<()> {
+    loop {
+        if used_bytes(conn)? <= cap {
+            return Ok(());
+        }
+
+        let with_output: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM commands WHERE output IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        if with_output > 0 {
+            let step = (with_output * CAP_STEP_PERCENT / 100).max(CAP_MIN_STEP);
+            reclaimed.outputs += drop_output_before(conn, i64::MAX, step)?;
+        } else {
+            let total: i64 = conn.query_row("SELECT COUNT(*) FROM commands", [], |row| row.get(0))?;
+            let step = (total * CAP_STEP_PERCENT / 100).max(CAP_MIN_STEP) as usize;
+            let deleted = delete_commands_before(conn, i64::MAX, keep, step)?;
+            if deleted == 0 {
+                reclaimed.over_cap = true;
+                return Ok(());
+            }
+            reclaimed.commands += deleted;
+        }
+        // Deleted text stays in the index's segments until they are merged,
+        // so measure only after merging.
+        optimize(conn)?;
+    }
+}
+
+/// Bytes in pages that hold data, ignoring free pages a VACUUM would return.
+pub fn used_bytes(conn: &Connection) -> Result<i64> {
+    let (pages, free, size): (i64, i64, i64) = conn.query_row(
+        "SELECT (SELECT page_count FROM pragma_page_count()),
+                (SELECT freelist_count FROM pragma_freelist_count()),
+                (SELECT page_size FROM pragma_page_size())",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    Ok((pages - free) * size)
+}
+
+/// Bytes the database file occupies, free pages included.
+pub fn file_bytes(conn: &Connection) -> Result<i64> {
+    let bytes = conn.query_row(
+        "SELECT (SELECT page_count FROM pragma_page_count())
+              * (SELECT page_size FROM pragma_page_size())",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(bytes)
+}
+
+/// Merge every FTS index down to one segment, discarding deleted entries.
+pub fn optimize(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "INSERT INTO commands_fts(commands_fts) VALUES ('optimize');
+         INSERT INTO commands_trigram(commands_trigram) VALUES ('optimize');
+         INSERT INTO summaries_fts(summaries_fts) VALUES ('optimize');
+         INSERT INTO ai_chunks_fts(ai_chunks_fts) VALUES ('optimize');
+         INSERT INTO ai_chunks_trigram(ai_chunks_trigram) VALUES ('optimize');",
+    )
+    .context("Failed to optimize search indexes")
+}
+
+/// Rewrite the file without its free pages.
+pub fn vacuum(conn: &Connection) -> Result<()> {
+    conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")
+        .context("Failed to vacuum the database")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{Command, Session};
+    use crate::db::queries;
+    use crate::db::schema::initialize_db;
+
+    const DAY: i64 = 86_400_000;
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        initialize_db(&conn).unwrap();
+        conn
+    }
+
+    fn add(conn: &Connection, session: &str, text: &str, at: i64, output: Option<&str>) {
+        queries::insert_session(
+            conn,
+            &Session {
+                id: session.into(),
+                start_time: at,
+                end_time: None,
+                terminal_app: None,
+                initial_dir: None,
+            },
+        )
+        .unwrap();
+        queries::insert_command(
+            conn,
+            &Command {
+                id: None,
+                session_id: session.into(),
+                command_text: text.into(),
+                timestamp: at,
+                duration_ms: None,
+                cwd: None,
+                git_repo: None,
+                git_branch: None,
+                exit_code: Some(0),
+                output: output.map(String::from),
+            },
+        )
+        .unwrap();
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn old_output_goes_before_old_commands() {
+        let conn = db();
+        let now = 100 * DAY;
+        add(&conn, "old", "make", 10 * DAY, Some("compiling"));
+        add(&conn, "new", "make", 99 * DAY, Some("compiling"));
+
+        let policy = RetentionConfig {
+            output_days: Some(30),
+            ..RetentionConfig::default()
+        };
+        let reclaimed = apply(&conn, &policy, now).unwrap();
+        assert_eq!(reclaimed.outputs, 1);
+        assert_eq!(reclaimed.commands, 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM commands WHERE output IS NOT NULL"), 1);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM commands_fts WHERE commands_fts MATCH 'compiling'"),
+            1
+        );
+    }
+
+    #[test]
+    fn kept_commands_outlive_the_age_limit() {
+        let conn = db();
+        let now = 400 * DAY;
+        add(&conn, "a", "ls -la", DAY, None);
+        add(&conn, "b", "kubectl rollout undo deploy/api", DAY, None);
+
+        let policy = RetentionConfig {
+            command_days: Some(365),
+            keep_patterns: vec!["kubectl rollout*".into()],
+            ..RetentionConfig::default()
+        };
+        let reclaimed = apply(&conn, &policy, now).unwrap();
+        assert_eq!(reclaimed.commands, 1);
+        assert_eq!(reclaimed.sessions, 1, "session a is left empty");
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM commands"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM commands_fts WHERE commands_fts MATCH 'ls'"), 0);
+    }
+
+    #[test]
+    fn a_session_with_a_summary_survives_losing_its_commands() {
+        let conn = db();
+        add(&conn, "s", "make", DAY, None);
+        queries::insert_summary(
+            &conn,
+            &crate::db::models::Summary {
+                id: None,
+                session_id: "s".into(),
+                summary_text: "built the release".into(),
+                tags: None,
+                intent: None,
+                created_at: DAY,
+                started_at: Some(DAY),
+                ended_at: Some(DAY),
+            },
+        )
+        .unwrap();
+
+        let policy = RetentionConfig {
+            command_days: Some(1),
+            ..RetentionConfig::default()
+        };
+        let reclaimed = apply(&conn, &policy, 10 * DAY).unwrap();
+        assert_eq!(reclaimed.commands, 1);
+        assert_eq!(reclaimed.sessions, 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM summaries"), 1);
+    }
+
+    #[test]
+    fn the_size_cap_trims_the_oldest_output_first() {
+        let conn = db();
+        let output = "x".repeat(8_000);
+        for i in 0..300 {
+            add(&conn, "s", &format!("build {}", i), i, Some(&output));
+        }
+        let cap = used_bytes(&conn).unwrap() * 3 / 4;
+        let mut reclaimed = Reclaimed::default();
+        enforce_cap(&conn, cap, &[], &mut reclaimed).unwrap();
+
+        assert!(used_bytes(&conn).unwrap() <= cap);
+        assert!(reclaimed.outputs > 0);
+        assert_eq!(reclaimed.commands, 0);
+        let newest_kept: i64 =
+            count(&conn, "SELECT COUNT(*) FROM commands WHERE output IS NOT NULL AND timestamp = 299");
+        assert_eq!(newest_kept, 1);
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
 
+use super::crypto;
+
 pub fn initialize_db(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "
@@ -39,6 +41,9 @@ pub fn initialize_db(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_commands_timestamp ON commands(timestamp);
         CREATE INDEX IF NOT EXISTS idx_commands_exit_code ON commands(exit_code);
         CREATE INDEX IF NOT EXISTS idx_commands_git_repo ON commands(git_repo);
+        CREATE INDEX IF NOT EXISTS idx_commands_git_branch ON commands(git_branch);
+        CREATE INDEX IF NOT EXISTS idx_commands_cwd ON commands(cwd);
+        CREATE INDEX IF NOT EXISTS idx_commands_duration ON commands(duration_ms);
         ",
     )
     .context("Failed to create base tables")?;
@@ -53,6 +58,19 @@ pub fn initialize_db(conn: &Connection) -> Result<()> {
             .context("Failed to add output column")?;
     }
 
+    // Migrate: summaries cover a work session, not always a whole shell session
+    let has_summary_span: bool = conn
+        .prepare("SELECT 1 FROM pragma_table_info('summaries') WHERE name='ended_at'")?
+        .exists([])?;
+
+    if !has_summary_span {
+        conn.execute_batch(
+            "ALTER TABLE summaries ADD COLUMN started_at INTEGER;
+             ALTER TABLE summaries ADD COLUMN ended_at INTEGER;",
+        )
+        .context("Failed to add summary span columns")?;
+    }
+
     // Create FTS5 tables (these don't support IF NOT EXISTS, so check first)
     let has_commands_fts: bool = conn
         .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='commands_fts'")?
@@ -105,7 +123,47 @@ pub fn initialize_db(conn: &Connection) -> Result<()> {
         }
     }
 
+    // Migrate: keep commands_fts in step with deletes and edits. Databases
+    // from before these triggers can hold text for rows deleted by hand, so
+    // the index is rebuilt the first time they are added.
+    let has_commands_ad: bool = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type='trigger' AND name='commands_ad'")?
+        .exists([])?;
+
+    if !has_commands_ad {
+        conn.execute_batch(
+            "
+            CREATE TRIGGER commands_ad AFTER DELETE ON commands BEGIN
+                INSERT INTO commands_fts(commands_fts, rowid, command_text, cwd, git_repo, git_branch, output)
+                VALUES ('delete', old.id, old.command_text, old.cwd, old.git_repo, old.git_branch, old.output);
+            END;
+
+            CREATE TRIGGER commands_au AFTER UPDATE ON commands BEGIN
+                INSERT INTO commands_fts(commands_fts, rowid, command_text, cwd, git_repo, git_branch, output)
+                VALUES ('delete', old.id, old.command_text, old.cwd, old.git_repo, old.git_branch, old.output);
+                INSERT INTO commands_fts(rowid, command_text, cwd, git_repo, git_branch, output)
+                VALUES (new.id, new.command_text, new.cwd, new.git_repo, new.git_branch, new.output);
+            END;
+
+            INSERT INTO commands_fts(commands_fts) VALUES ('rebuild');
+            ",
+        )
+        .context("Failed to add commands FTS delete and update triggers")?;
+    }
+
     initialize_ai_tables(conn)?;
+    initialize_trigram_tables(conn)?;
+    initialize_embedding_tables(conn)?;
+
+    // Every word the indexes hold, for "did you mean" suggestions. These
+    // read the FTS tables directly and store nothing of their own.
+    conn.execute_batch(
+        "
+        CREATE VIRTUAL TABLE IF NOT EXISTS commands_vocab USING fts5vocab(commands_fts, row);
+        CREATE VIRTUAL TABLE IF NOT EXISTS ai_chunks_vocab USING fts5vocab(ai_chunks_fts, row);
+        ",
+    )
+    .context("Failed to create search vocabulary tables")?;
 
     let has_summaries_fts: bool = conn
         .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='summaries_fts'")?
@@ -128,6 +186,32 @@ pub fn initialize_db(conn: &Connection) -> Result<()> {
         .context("Failed to create summaries FTS table")?;
     }
 
+    // Migrate: the same delete and update triggers for summaries_fts.
+    let has_summaries_ad: bool = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type='trigger' AND name='summaries_ad'")?
+        .exists([])?;
+
+    if !has_summaries_ad {
+        conn.execute_batch(
+            "
+            CREATE TRIGGER summaries_ad AFTER DELETE ON summaries BEGIN
+                INSERT INTO summaries_fts(summaries_fts, rowid, summary_text, tags)
+                VALUES ('delete', old.id, old.summary_text, old.tags);
+            END;
+
+            CREATE TRIGGER summaries_au AFTER UPDATE ON summaries BEGIN
+                INSERT INTO summaries_fts(summaries_fts, rowid, summary_text, tags)
+                VALUES ('delete', old.id, old.summary_text, old.tags);
+                INSERT INTO summaries_fts(rowid, summary_text, tags)
+                VALUES (new.id, new.summary_text, new.tags);
+            END;
+
+            INSERT INTO summaries_fts(summaries_fts) VALUES ('rebuild');
+            ",
+        )
+        .context("Failed to add summaries FTS delete and update triggers")?;
+    }
+
     Ok(())
 }
 
@@ -226,12 +310,152 @@ fn initialize_ai_tables(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Trigram indexes over command text and agent chunks, for fuzzy search.
+///
+/// The word indexes only match whole tokens. These index every run of three
+/// characters instead, so a fragment from mid-word — or CJK text, which has
+/// no spaces to split on — is still an index lookup rather than a `LIKE`
+/// scan over everything. They are built from existing rows the first time.
+fn initialize_trigram_tables(conn: &Connection) -> Result<()> {
+    let has_commands_trigram: bool = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='commands_trigram'")?
+        .exists([])?;
+
+    if !has_commands_trigram {
+        conn.execute_batch(
+            "
+            CREATE VIRTUAL TABLE commands_trigram USING fts5(
+                command_text,
+                content='commands', content_rowid='id', tokenize='trigram'
+            );
+
+            CREATE TRIGGER commands_trigram_ai AFTER INSERT ON commands BEGIN
+                INSERT INTO commands_trigram(rowid, command_text) VALUES (new.id, new.command_text);
+            END;
+
+            CREATE TRIGGER commands_trigram_ad AFTER DELETE ON commands BEGIN
+                INSERT INTO commands_trigram(commands_trigram, rowid, command_text)
+                VALUES ('delete', old.id, old.command_text);
+            END;
+
+            CREATE TRIGGER commands_trigram_au AFTER UPDATE OF command_text ON commands BEGIN
+                INSERT INTO commands_trigram(commands_trigram, rowid, command_text)
+                VALUES ('delete', old.id, old.command_text);
+                INSERT INTO commands_trigram(rowid, command_text) VALUES (new.id, new.command_text);
+            END;
+
+            INSERT INTO commands_trigram(commands_trigram) VALUES ('rebuild');
+            ",
+        )
+        .context("Failed to create commands trigram table")?;
+    }
+
+    let has_ai_chunks_trigram: bool = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='ai_chunks_trigram'")?
+        .exists([])?;
+
+    if !has_ai_chunks_trigram {
+        conn.execute_batch(
+            "
+            CREATE VIRTUAL TABLE ai_chunks_trigram USING fts5(
+                text,
+                content='ai_chunks', content_rowid='id', tokenize='trigram'
+            );
+
+            CREATE TRIGGER ai_chunks_trigram_ai AFTER INSERT ON ai_chunks BEGIN
+                INSERT INTO ai_chunks_trigram(rowid, text) VALUES (new.id, new.text);
+            END;
+
+            CREATE TRIGGER ai_chunks_trigram_ad AFTER DELETE ON ai_chunks BEGIN
+                INSERT INTO ai_chunks_trigram(ai_chunks_trigram, rowid, text)
+                VALUES ('delete', old.id, old.text);
+            END;
+
+            CREATE TRIGGER ai_chunks_trigram_au AFTER UPDATE OF text ON ai_chunks BEGIN
+                INSERT INTO ai_chunks_trigram(ai_chunks_trigram, rowid, text)
+                VALUES ('delete', old.id, old.text);
+                INSERT INTO ai_chunks_trigram(rowid, text) VALUES (new.id, new.text);
+            END;
+
+            INSERT INTO ai_chunks_trigram(ai_chunks_trigram) VALUES ('rebuild');
+            ",
+        )
+        .context("Failed to create AI chunk trigram table")?;
+    }
+
+    Ok(())
+}
+
+/// Vectors for semantic search, one per command and per agent chunk, tagged
+/// with the model that made them. Triggers drop a vector when its row is
+/// deleted or its text changes — a redacted command must not stay findable
+/// by what it used to say.
+fn initialize_embedding_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS command_embeddings (
+            command_id INTEGER PRIMARY KEY,
+            model TEXT NOT NULL,
+            vector BLOB NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS chunk_embeddings (
+            chunk_id INTEGER PRIMARY KEY,
+            model TEXT NOT NULL,
+            vector BLOB NOT NULL
+        );
+
+        CREATE TRIGGER IF NOT EXISTS command_embeddings_ad AFTER DELETE ON commands BEGIN
+            DELETE FROM command_embeddings WHERE command_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS command_embeddings_au AFTER UPDATE OF command_text ON commands BEGIN
+            DELETE FROM command_embeddings WHERE command_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chunk_embeddings_ad AFTER DELETE ON ai_chunks BEGIN
+            DELETE FROM chunk_embeddings WHERE chunk_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS chunk_embeddings_au AFTER UPDATE OF text ON ai_chunks BEGIN
+            DELETE FROM chunk_embeddings WHERE chunk_id = old.id;
+        END;
+        ",
+    )
+    .context("Failed to create embedding tables")?;
+    Ok(())
+}
+
 pub fn open_db() -> Result<Connection> {
     let db_path = crate::config::settings::db_path();
     crate::config::settings::ensure_recall_dir()?;
+    // Only an encrypted or brand-new database needs the key, so a plaintext
+    // one never waits on a key_command.
+    let encrypted = crypto::is_encrypted(&db_path)?;
+    let key = if encrypted || !db_path.exists() {
+        crypto::configured_key()?
+    } else {
+        None
+    };
     let conn = Connection::open(&db_path)
         .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+    match key {
+        Some(key) => crypto::unlock(&conn, &db_path, &key)?,
+        None if encrypted => return Err(crypto::missing_key(&db_path)),
+        None => {}
+    }
+    // Hooks log in the background, so several recall processes can open the
+    // database at once: wait for each other rather than fail, and take turns
+    // at the migrations so none of them runs twice.
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
     conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
-    initialize_db(&conn)?;
+    conn.execute_batch("BEGIN IMMEDIATE")?;
+    match initialize_db(&conn) {
+        Ok(()) => conn.execute_batch("COMMIT")?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
     Ok(conn)
 }
@@ -0,0 +1,114 @@
+//! Work sessions: the stretches of a shell session worth treating as one.
+//!
+//! A shell session lasts as long as the terminal does, and a terminal can stay
+//! open for days. Anything that presents or summarizes "a session" wants the
+//! burst of work instead, so commands are split wherever the shell sat idle
+//! for longer than the configured gap, as well as wherever the shell changes.
+
+use std::borrow::Borrow;
+
+use super::models::Command;
+
+/// Split commands, already in time order, into work sessions: runs from the
+/// same shell session with no pause longer than `idle_gap_ms` between one
+/// command finishing and the next starting.
+pub fn split<T: Borrow<Command>>(commands: &[T], idle_gap_ms: i64) -> Vec<&[T]> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for i in 1..commands.len() {
+        let prev = commands[i - 1].borrow();
+        let next = commands[i].borrow();
+        let finished = prev.timestamp + prev.duration_ms.unwrap_or(0).max(0);
+        if next.session_id != prev.session_id || next.timestamp - finished > idle_gap_ms {
+            groups.push(&commands[start..i]);
+            start = i;
+        }
+    }
+    if start < commands.len() {
+        groups.push(&commands[start..]);
+    }
+    groups
+}
+
+/// When the last command of a work session finished.
+pub fn finished_at<T: Borrow<Command>>(commands: &[T]) -> Option<i64> {
+    commands
+        .iter()
+        .map(|c| {
+            let c = c.borrow();
+            c.timestamp + c.duration_ms.unwrap_or(0).max(0)
+        })
+        .max()
+}
+
+/// Whether more commands may still join a work session: its shell has not
+/// exited and has not yet been idle for a full gap.
+pub fn is_open<T: Borrow<Command>>(
+    commands: &[T],
+    session_ended: bool,
+    idle_gap_ms: i64,
+    now: i64,
+) -> bool {
+    !session_ended && finished_at(commands).is_some_and(|at| now - at <= idle_gap_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60_000;
+
+    fn command(session: &str, at: i64, duration: Option<i64>) -> Command {
+        Command {
+            id: None,
+            session_id: session.into(),
+            command_text: "ls".into(),
+            timestamp: at,
+            duration_ms: duration,
+            cwd: None,
+            git_repo: None,
+            git_branch: None,
+            exit_code: Some(0),
+            output: None,
+        }
+    }
+
+    #[test]
+    fn a_long_idle_gap_starts_a_new_work_session() {
+        let commands = vec![
+            command("s", 0, None),
+            command("s", 5 * MINUTE, None),
+            command("s", 3 * 24 * 60 * MINUTE, None),
+        ];
+        let groups = split(&commands, 30 * MINUTE);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(groups[1].len(), 1);
+    }
+
+    #[test]
+    fn the_gap_is_measured_from_when_a_command_finished() {
+        // A 50-minute build followed by a command right after it is one stretch
+        // of work, not two.
+        let commands = vec![
+            command("s", 0, Some(50 * MINUTE)),
+            command("s", 51 * MINUTE, None),
+        ];
+        assert_eq!(split(&commands, 30 * MINUTE).len(), 1);
+    }
+
+    #[test]
+    fn a_different_shell_is_always_a_different_work_session() {
+        let commands = [command("a", 0, None), command("b", MINUTE, None)];
+        let refs: Vec<&Command> = commands.iter().collect();
+        assert_eq!(split(&refs, 30 * MINUTE).len(), 2);
+    }
+
+    #[test]
+    fn a_work_session_closes_when_its_shell_exits_or_goes_idle() {
+        let commands = [command("s", 0, Some(MINUTE))];
+        assert!(is_open(&commands, false, 30 * MINUTE, 10 * MINUTE));
+        assert!(!is_open(&commands, true, 30 * MINUTE, 10 * MINUTE));
+        assert!(!is_open(&commands, false, 30 * MINUTE, 40 * MINUTE));
+    }
+}
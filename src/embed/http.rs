@@ -0,0 +1,173 @@
+//! An embedder behind an OpenAI-compatible `/embeddings` endpoint — what
+//! Ollama, llama.cpp's server and LM Studio all serve locally.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+use super::Embedder;
+
+/// A batch of long chunks on a CPU can take a while; a dead server should not.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct HttpEmbedder {
+    url: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    /// Where the input was in the request; servers may answer out of order.
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+impl HttpEmbedder {
+    pub fn new(endpoint: &str, model: &str) -> Self {
+        Self {
+            url: format!("{}/embeddings", endpoint.trim_end_matches('/')),
+            model: model.to_string(),
+        }
+    }
+
+    async fn request(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+        let response = client
+            .post(&self.url)
+            .json(&EmbeddingRequest {
+                model: &self.model,
+                input: texts,
+            })
+            .send()
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not reach the embedding model at {}. Is it running (`ollama serve`)?",
+                    self.url
+                )
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("Embedding model error ({}): {}", status, body.trim());
+        }
+
+        let mut reply: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Failed to parse the embedding model's response")?;
+        reply.data.sort_by_key(|data| data.index);
+        Ok(reply.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        block_on(self.request(texts))?
+    }
+}
+
+/// Search and indexing are synchronous, but reqwest is not. Inside the CLI's
+/// runtime this borrows the current worker; elsewhere (the TUI's thread,
+/// tests) it starts a small runtime of its own.
+fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => Ok(tokio::task::block_in_place(|| handle.block_on(future))),
+        Err(_) => Ok(tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(future)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer one request with `body`, handing back what was asked.
+    fn serve_once(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // Read the headers, then as much body as they announce.
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn speaks_the_openai_embeddings_api() {
+        let (endpoint, server) = serve_once(
+            r#"{"data":[{"index":1,"embedding":[0.0,1.0]},{"index":0,"embedding":[1.0,0.0]}]}"#,
+        );
+        let embedder = HttpEmbedder::new(&endpoint, "nomic-embed-text");
+        let vectors = embedder
+            .embed(&["first".to_string(), "second".to_string()])
+            .unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]], "put back in order");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/embeddings "));
+        assert!(request.contains(r#""model":"nomic-embed-text""#));
+        assert!(request.contains(r#""input":["first","second"]"#));
+    }
+
+    #[test]
+    fn an_unreachable_model_says_where_it_looked() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1", listener.local_addr().unwrap());
+        drop(listener);
+        let error = HttpEmbedder::new(&endpoint, "m")
+            .embed(&["x".to_string()])
+            .unwrap_err();
+        assert!(format!("{}", error).contains(&endpoint));
+    }
+}
@@ -0,0 +1,308 @@
+//! Semantic search: vectors for commands and agent chunks, from a local
+//! embedding model.
+//!
+//! Full-text search only finds the words you typed; "flaky retries" misses a
+//! conversation about "intermittent backoff failures". An embedding model
+//! maps both to nearby vectors. recall stores one vector per command and per
+//! agent chunk next to the FTS5 indexes, and hybrid search blends nearness
+//! with BM25. The model runs locally — nothing leaves the machine, and once
+//! it is downloaded everything works offline.
+
+pub mod http;
+pub mod store;
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::config::settings::EmbeddingsConfig;
+
+/// Longer texts are cut before embedding; small local models have short
+/// context windows, and the opening of a chunk carries most of its meaning.
+const MAX_CHARS: usize = 4000;
+
+/// Turns text into vectors. Every vector from one embedder has the same
+/// length, and only vectors from the same model are compared.
+pub trait Embedder {
+    /// Recorded next to each vector, so changing models re-embeds everything.
+    fn model(&self) -> &str;
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// The embedder from `[embeddings]` in config.toml, if one is set up.
+pub fn from_config(config: &EmbeddingsConfig) -> Option<Box<dyn Embedder>> {
+    let endpoint = config.endpoint.as_deref()?.trim();
+    if endpoint.is_empty() {
+        return None;
+    }
+    Some(Box::new(http::HttpEmbedder::new(endpoint, &config.model)))
+}
+
+/// The configured embedder, or an error saying how to set one up.
+pub fn configured() -> Result<Box<dyn Embedder>> {
+    let config = crate::config::settings::load_config()?;
+    match from_config(&config.embeddings) {
+        Some(embedder) => Ok(embedder),
+        None => bail!(
+            "Semantic search needs a local embedding model. With Ollama:\n  \
+             ollama pull nomic-embed-text\n  \
+             and in ~/.recall/config.toml:\n  \
+             [embeddings]\n  \
+             endpoint = \"http://localhost:11434/v1\""
+        ),
+    }
+}
+
+/// `recall embed` — bring the vectors up to date with history.
+pub fn run() -> Result<()> {
+    let config = crate::config::settings::load_config()?;
+    let embedder = configured()?;
+    let conn = crate::db::schema::open_db()?;
+
+    println!();
+    println!(
+        "  {} {}  {}",
+        "◉".cyan(),
+        "Embedding".bold(),
+        embedder.model().dimmed()
+    );
+    println!("  {}", "─".repeat(60).dimmed());
+
+    let report = backfill(&conn, embedder.as_ref(), config.embeddings.batch_size, |done| {
+        eprint!("\r  {} {}", "│".dimmed(), format!("{} embedded", done).dimmed());
+    })?;
+    if report.commands + report.chunks > 0 {
+        eprintln!();
+    }
+
+    let (commands, chunks) = store::counts(&conn, embedder.model())?;
+    println!(
+        "  {} {} commands, {} agent chunks {}",
+        "✓".green(),
+        report.commands,
+        report.chunks,
+        "newly embedded".dimmed()
+    );
+    println!(
+        "  {}",
+        format!("{} commands and {} chunks searchable by meaning", commands, chunks).dimmed()
+    );
+    println!();
+    println!(
+        "  {}",
+        "recall search --hybrid <query>  ·  recall agents search --hybrid <query>".dimmed()
+    );
+    println!();
+    Ok(())
+}
+
+/// What a backfill did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub commands: usize,
+    pub chunks: usize,
+}
+
+/// Embed every command and chunk that has no vector from this model yet,
+/// `batch` texts per request. `progress` hears the running total after each
+/// batch, so a first run over a long history can show it is moving.
+pub fn backfill(
+    conn: &Connection,
+    embedder: &dyn Embedder,
+    batch: usize,
+    mut progress: impl FnMut(usize),
+) -> Result<Report> {
+    let batch = batch.max(1);
+    let mut report = Report::default();
+
+    loop {
+        let pending = store::pending_commands(conn, embedder.model(), batch)?;
+        if pending.is_empty() {
+            break;
+        }
+        let vectors = embed_all(embedder, &pending)?;
+        for ((id, _), vector) in pending.iter().zip(&vectors) {
+            store::save_command(conn, *id, embedder.model(), vector)?;
+        }
+        report.commands += pending.len();
+        progress(report.commands + report.chunks);
+    }
+
+    loop {
+        let pending = store::pending_chunks(conn, embedder.model(), batch)?;
+        if pending.is_empty() {
+            break;
+        }
+        let vectors = embed_all(embedder, &pending)?;
+        for ((id, _), vector) in pending.iter().zip(&vectors) {
+            store::save_chunk(conn, *id, embedder.model(), vector)?;
+        }
+        report.chunks += pending.len();
+        progress(report.commands + report.chunks);
+    }
+
+    Ok(report)
+}
+
+fn embed_all(embedder: &dyn Embedder, rows: &[(i64, String)]) -> Result<Vec<Vec<f32>>> {
+    let texts: Vec<String> = rows.iter().map(|(_, text)| clip(text)).collect();
+    let vectors = embedder.embed(&texts)?;
+    if vectors.len() != texts.len() {
+        bail!(
+            "The embedding model returned {} vectors for {} texts",
+            vectors.len(),
+            texts.len()
+        );
+    }
+    Ok(vectors)
+}
+
+/// Embed the search text itself.
+pub fn embed_query(embedder: &dyn Embedder, text: &str) -> Result<Vec<f32>> {
+    match embedder.embed(&[clip(text)])?.pop() {
+        Some(vector) => Ok(vector),
+        None => bail!("The embedding model returned no vector"),
+    }
+}
+
+fn clip(text: &str) -> String {
+    text.chars().take(MAX_CHARS).collect()
+}
+
+/// Cosine similarity, 1.0 for the same direction. Vectors of different
+/// lengths — from different models — are unrelated.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+/// Vectors are stored as little-endian `f32`s.
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+pub fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+/// Blend ranked lists — BM25 order and nearness order — by reciprocal rank,
+/// best first. Something both lists rank well beats something only one
+/// does, and neither scale has to be comparable with the other.
+pub fn blend<K: Hash + Eq + Clone>(lists: &[Vec<K>]) -> Vec<(K, f64)> {
+    let mut scores: HashMap<K, f64> = HashMap::new();
+    let mut order = Vec::new();
+    for list in lists {
+        for (place, key) in list.iter().enumerate() {
+            let score = scores.entry(key.clone()).or_insert_with(|| {
+                order.push(key.clone());
+                0.0
+            });
+            *score += crate::search::unified::rrf(place);
+        }
+    }
+    let mut blended: Vec<(K, f64)> = order
+        .into_iter()
+        .map(|key| {
+            let score = scores[&key];
+            (key, score)
+        })
+        .collect();
+    // Stable, so equal scores keep the order the lists first gave them.
+    blended.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    blended
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A stand-in model: one dimension per idea, with synonyms sharing one,
+    /// so tests can tell semantic hits from word matches.
+    pub struct Synonyms;
+
+    const IDEAS: [&[&str]; 4] = [
+        &["flaky", "intermittent", "sporadic"],
+        &["retries", "retry", "backoff"],
+        &["deploy", "rollout", "release"],
+        &["database", "postgres", "sqlite"],
+    ];
+
+    impl Embedder for Synonyms {
+        fn model(&self) -> &str {
+            "synonyms"
+        }
+
+        fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    IDEAS
+                        .iter()
+                        .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn vectors_round_trip_through_blobs() {
+        let vector = vec![0.5, -1.25, 3.0e-7];
+        assert_eq!(from_blob(&to_blob(&vector)), vector);
+    }
+
+    #[test]
+    fn cosine_ignores_length_and_mismatched_models() {
+        assert!((cosine(&[1.0, 0.0], &[3.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[1.0, 0.0], &[0.0, 2.0]), 0.0);
+        assert_eq!(cosine(&[1.0, 0.0], &[1.0, 0.0, 0.0]), 0.0);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn blending_favours_what_both_lists_rank_well() {
+        let blended = blend(&[vec!["a", "b", "c"], vec!["b", "d"]]);
+        let keys: Vec<&str> = blended.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec!["b", "a", "d", "c"]);
+    }
+
+    #[test]
+    fn backfill_embeds_only_what_is_missing() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, start_time) VALUES ('s', 0);
+             INSERT INTO commands (session_id, command_text, timestamp) VALUES
+                 ('s', 'retry the deploy', 1), ('s', 'psql', 2);",
+        )
+        .unwrap();
+
+        let mut seen = Vec::new();
+        let report = backfill(&conn, &Synonyms, 1, |done| seen.push(done)).unwrap();
+        assert_eq!(report, Report { commands: 2, chunks: 0 });
+        assert_eq!(seen, vec![1, 2]);
+        assert_eq!(backfill(&conn, &Synonyms, 8, |_| {}).unwrap(), Report::default());
+
+        // Editing a command's text drops its vector, so it is embedded again.
+        conn.execute("UPDATE commands SET command_text = 'psql -c' WHERE id = 2", [])
+            .unwrap();
+        assert_eq!(backfill(&conn, &Synonyms, 8, |_| {}).unwrap().commands, 1);
+    }
+}
@@ -0,0 +1,202 @@
+//! SQL for the embedding tables: what still needs a vector, and which rows
+//! lie nearest a query.
+//!
+//! Nearness is worked out by scanning every stored vector for the model.
+//! That is plenty fast for one person's history, and keeps recall free of a
+//! vector-index extension.
+
+use anyhow::Result;
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, OptionalExtension};
+use std::collections::HashMap;
+
+use super::{cosine, from_blob, to_blob};
+use crate::ai::models::AiSearchResult;
+use crate::ai::store::{self as ai_store, Filter};
+use crate::db::models::{Command, SearchResult};
+use crate::search::query::Query;
+
+/// Commands with no vector from `model`, oldest first.
+pub fn pending_commands(conn: &Connection, model: &str, limit: usize) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.command_text FROM commands c
+         LEFT JOIN command_embeddings e ON e.command_id = c.id AND e.model = ?1
+         WHERE e.command_id IS NULL
+         ORDER BY c.id
+         LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![model, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+/// Agent chunks with no vector from `model`, oldest first.
+pub fn pending_chunks(conn: &Connection, model: &str, limit: usize) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.text FROM ai_chunks c
+         LEFT JOIN chunk_embeddings e ON e.chunk_id = c.id AND e.model = ?1
+         WHERE e.chunk_id IS NULL
+         ORDER BY c.id
+         LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![model, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+pub fn save_command(conn: &Connection, id: i64, model: &str, vector: &[f32]) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO command_embeddings (command_id, model, vector) VALUES (?1, ?2, ?3)",
+        params![id, model, to_blob(vector)],
+    )?;
+    Ok(())
+}
+
+pub fn save_chunk(conn: &Connection, id: i64, model: &str, vector: &[f32]) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO chunk_embeddings (chunk_id, model, vector) VALUES (?1, ?2, ?3)",
+        params![id, model, to_blob(vector)],
+    )?;
+    Ok(())
+}
+
+/// How many commands and chunks have a vector from `model`.
+pub fn counts(conn: &Connection, model: &str) -> Result<(usize, usize)> {
+    let count = |table: &str| -> Result<usize> {
+        let sql = format!("SELECT COUNT(*) FROM {} WHERE model = ?", table);
+        Ok(conn.query_row(&sql, [model], |row| row.get::<_, i64>(0))? as usize)
+    };
+    Ok((count("command_embeddings")?, count("chunk_embeddings")?))
+}
+
+/// The commands nearest `vector` that the query's filters let through, best
+/// first. `rank` is the negated similarity, so lower is better as with BM25.
+pub fn nearest_commands(
+    conn: &Connection,
+    model: &str,
+    vector: &[f32],
+    query: &Query,
+    limit: usize,
+) -> Result<Vec<SearchResult>> {
+    let (where_sql, mut values) = query.command_clauses("c");
+    let sql = format!(
+        "SELECT c.id, e.vector FROM command_embeddings e
+         JOIN commands c ON c.id = e.command_id
+         WHERE e.model = ?{}",
+        where_sql
+    );
+    values.insert(0, SqlValue::Text(model.to_string()));
+
+    let mut scored = Vec::new();
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(params_from_iter(values))?;
+    while let Some(row) = rows.next()? {
+        let blob: Vec<u8> = row.get(1)?;
+        scored.push((row.get::<_, i64>(0)?, cosine(vector, &from_blob(&blob))));
+    }
+    best_first(&mut scored, limit);
+
+    let mut stmt = conn.prepare(
+        "SELECT id, session_id, command_text, timestamp, duration_ms, cwd,
+                git_repo, git_branch, exit_code, output
+         FROM commands WHERE id = ?",
+    )?;
+    let mut results = Vec::new();
+    for (id, similarity) in scored {
+        let command = stmt
+            .query_row([id], |row| {
+                Ok(Command {
+                    id: Some(row.get(0)?),
+                    session_id: row.get(1)?,
+                    command_text: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    duration_ms: row.get(4)?,
+                    cwd: row.get(5)?,
+                    git_repo: row.get(6)?,
+                    git_branch: row.get(7)?,
+                    exit_code: row.get(8)?,
+                    output: row.get(9)?,
+                })
+            })
+            .optional()?;
+        if let Some(command) = command {
+            results.push(SearchResult {
+                command,
+                rank: -(similarity as f64),
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// The sessions whose chunks lie nearest `vector`, one hit per session with
+/// the opening of its nearest chunk, best first.
+pub fn nearest_sessions(
+    conn: &Connection,
+    model: &str,
+    vector: &[f32],
+    filter: &Filter,
+) -> Result<Vec<AiSearchResult>> {
+    let (where_sql, mut values) = filter.clauses("s");
+    let sql = format!(
+        "SELECT s.uid, c.id, e.vector FROM chunk_embeddings e
+         JOIN ai_chunks c ON c.id = e.chunk_id
+         JOIN ai_sessions s ON s.uid = c.session_uid
+         WHERE e.model = ?{}",
+        where_sql
+    );
+    values.insert(0, SqlValue::Text(model.to_string()));
+
+    // Each session's nearest chunk.
+    let mut best: HashMap<String, (i64, f32)> = HashMap::new();
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(params_from_iter(values))?;
+    while let Some(row) = rows.next()? {
+        let blob: Vec<u8> = row.get(2)?;
+        let similarity = cosine(vector, &from_blob(&blob));
+        let chunk: i64 = row.get(1)?;
+        best.entry(row.get(0)?)
+            .and_modify(|held| {
+                if similarity > held.1 {
+                    *held = (chunk, similarity);
+                }
+            })
+            .or_insert((chunk, similarity));
+    }
+
+    let mut scored: Vec<(String, f32)> = best.iter().map(|(uid, (_, sim))| (uid.clone(), *sim)).collect();
+    best_first(&mut scored, filter.limit);
+
+    let mut results = Vec::new();
+    for (uid, similarity) in scored {
+        let Some(session) = ai_store::get_session(conn, &uid)? else {
+            continue;
+        };
+        let text: String = conn.query_row(
+            "SELECT text FROM ai_chunks WHERE id = ?",
+            [best[&uid].0],
+            |row| row.get(0),
+        )?;
+        results.push(AiSearchResult {
+            session,
+            snippet: opening(&text),
+            rank: -(similarity as f64),
+        });
+    }
+    Ok(results)
+}
+
+/// Keep the `limit` nearest. Nothing at a right angle or beyond is near at all.
+fn best_first<K>(scored: &mut Vec<(K, f32)>, limit: usize) {
+    scored.retain(|(_, similarity)| *similarity > 0.0);
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(limit);
+}
+
+/// The start of a chunk on one line, for a snippet when no words matched.
+fn opening(text: &str) -> String {
+    const WINDOW: usize = 120;
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > WINDOW {
+        format!("{}…", flat.chars().take(WINDOW).collect::<String>())
+    } else {
+        flat
+    }
+}
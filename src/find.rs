@@ -0,0 +1,136 @@
+//! `recall find` — one search across shell history, agent sessions and
+//! summaries, ranked together by [`crate::search::unified`].
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::ai::commands::render_snippet;
+use crate::search::query;
+use crate::search::unified::{self, Hit};
+
+const RULE: usize = 60;
+const WIDTH: usize = 90;
+
+pub fn run(input: &str, limit: usize) -> Result<()> {
+    let query = query::parse(input)?;
+    let conn = crate::db::schema::open_db()?;
+    let limit = limit.max(1);
+
+    let mut found = unified::search(&conn, &query, limit)?;
+    found.hits.truncate(limit);
+
+    if found.hits.is_empty() {
+        println!("\n  {} {}\n", "●".dimmed(), format!("Nothing matches \"{}\".", input).dimmed());
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {} {}  {}",
+        "◉".cyan(),
+        format!("Find: \"{}\"", input).bold(),
+        format!("{} results", found.hits.len()).dimmed()
+    );
+    println!("  {}", "─".repeat(RULE).dimmed());
+
+    for ranked in &found.hits {
+        print_hit(&ranked.hit);
+    }
+
+    println!();
+    if found.agent_mode == crate::ai::search::Mode::Fuzzy {
+        println!("  {}", "agent sessions matched as substrings".dimmed());
+    }
+    println!(
+        "  {}",
+        "recall agents resume <id>  to reopen a session".dimmed()
+    );
+    println!();
+    Ok(())
+}
+
+fn print_hit(hit: &Hit) {
+    match hit {
+        Hit::Command(result) => {
+            let cmd = &result.command;
+            let status = match cmd.exit_code {
+                Some(0) => "✓".green(),
+                Some(_) => "✗".red(),
+                None => "•".dimmed(),
+            };
+            println!(
+                "\n  {} {}  {:<7} {} {}",
+                "┌".dimmed(),
+                when(cmd.timestamp).white().bold(),
+                "shell".green(),
+                status,
+                one_line(&cmd.command_text)
+            );
+            let place = cmd
+                .git_repo
+                .clone()
+                .or_else(|| cmd.cwd.clone())
+                .unwrap_or_default();
+            if place.is_empty() {
+                println!("  {} {}", "└".dimmed(), short(&cmd.session_id).dimmed());
+            } else {
+                println!(
+                    "  {} {}  {}",
+                    "└".dimmed(),
+                    place.blue(),
+                    short(&cmd.session_id).dimmed()
+                );
+            }
+        }
+        Hit::Agent(result) => {
+            let session = &result.session;
+            let title = session.title.as_deref().map(one_line).unwrap_or_else(|| "(untitled)".into());
+            println!(
+                "\n  {} {}  {:<7} {}",
+                "┌".dimmed(),
+                when(session.last_activity).white().bold(),
+                session.source.as_str().magenta(),
+                title.white()
+            );
+            let project = session.project.rsplit('/').next().unwrap_or(&session.project);
+            println!(
+                "  {} {}  {}  {}",
+                "└".dimmed(),
+                project.blue(),
+                short(&session.session_id).dimmed(),
+                render_snippet(result.snippet.lines().next().unwrap_or(""), WIDTH)
+            );
+        }
+        Hit::Summary(result) => {
+            let summary = &result.summary;
+            println!(
+                "\n  {} {}  {:<7} {}",
+                "┌".dimmed(),
+                when(summary.ended_at.unwrap_or(summary.created_at)).white().bold(),
+                "summary".yellow(),
+                one_line(&summary.summary_text)
+            );
+            println!("  {} {}", "└".dimmed(), short(&summary.session_id).dimmed());
+        }
+    }
+}
+
+fn when(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn short(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
+/// The first line, cut to fit.
+fn one_line(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > WIDTH {
+        format!("{}…", line.chars().take(WIDTH - 1).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
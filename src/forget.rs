@@ -0,0 +1,198 @@
+//! `recall forget` — take commands back out of the history.
+//!
+//! Pausing and ignore patterns only help before a command is run. Once a
+//! password has been typed on the command line it is in the database, in the
+//! full-text index, and possibly in a summary written from it. Forgetting
+//! removes all of those, then has SQLite overwrite the freed pages so the
+//! text does not linger in the file either.
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use rusqlite::Connection;
+use std::io::{BufRead, IsTerminal, Write};
+
+use crate::db::models::Command;
+use crate::db::queries::{self, CommandSelection};
+use crate::search::dates;
+
+/// How many of the matching commands the preview lists.
+const PREVIEW: usize = 10;
+const RULE: usize = 60;
+
+/// What to forget, as given on the command line.
+pub struct Request {
+    pub pattern: Option<String>,
+    pub ids: Vec<i64>,
+    pub session: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub yes: bool,
+}
+
+pub fn run(request: Request) -> Result<()> {
+    let conn = crate::db::schema::open_db()?;
+    let selection = selection(&conn, &request)?;
+    if selection.is_empty() {
+        bail!("Say what to forget: a pattern, --id, --session, or a --since/--until range");
+    }
+
+    let commands = queries::select_commands(&conn, &selection)?;
+    if commands.is_empty() {
+        println!("\n  {} {}\n", "●".dimmed(), "Nothing matches; nothing to forget.".dimmed());
+        return Ok(());
+    }
+
+    print_preview(&commands);
+
+    if !request.yes && !confirm(commands.len())? {
+        println!("\n  {} {}\n", "●".dimmed(), "Nothing was forgotten.".dimmed());
+        return Ok(());
+    }
+
+    // Zero freed pages as rows are deleted, rather than leaving the text
+    // readable in the file until something reuses the space.
+    conn.execute_batch("PRAGMA secure_delete = ON;")?;
+    let deleted = queries::delete_commands(&conn, &commands)?;
+    scrub(&conn)?;
+
+    println!(
+        "\n  {} Forgot {} {}{}{}\n",
+        "✓".green(),
+        deleted.commands.to_string().bold(),
+        plural(deleted.commands, "command"),
+        if deleted.summaries > 0 {
+            format!(", {} {}", deleted.summaries, plural(deleted.summaries, "summary"))
+        } else {
+            String::new()
+        },
+        if deleted.sessions > 0 {
+            format!(", {} empty {}", deleted.sessions, plural(deleted.sessions, "session"))
+        } else {
+            String::new()
+        },
+    );
+    Ok(())
+}
+
+fn selection(conn: &Connection, request: &Request) -> Result<CommandSelection> {
+    let session_id = match &request.session {
+        Some(prefix) => {
+            let ids = queries::find_session_ids(conn, prefix)?;
+            match ids.len() {
+                0 => bail!("No session matches '{}'", prefix),
+                1 => ids.into_iter().next(),
+                n => bail!("'{}' matches {} sessions; give more of the id", prefix, n),
+            }
+        }
+        None => None,
+    };
+
+    Ok(CommandSelection {
+        text: request.pattern.clone().filter(|p| !p.is_empty()),
+        ids: request.ids.clone(),
+        session_id,
+        // A bare date as the upper bound means through the end of that day.
+        since: request.since.as_deref().map(dates::parse).transpose()?.map(|span| span.start),
+        until: request.until.as_deref().map(dates::parse).transpose()?.map(|span| span.end),
+    })
+}
+
+fn print_preview(commands: &[Command]) {
+    println!();
+    println!(
+        "  {} {} {}",
+        "◉".cyan(),
+        "Forget".bold(),
+        format!("{} {}", commands.len(), plural(commands.len(), "command")).dimmed()
+    );
+    println!("  {}", "─".repeat(RULE).dimmed());
+    for cmd in commands.iter().take(PREVIEW) {
+        let when = chrono::DateTime::from_timestamp_millis(cmd.timestamp)
+            .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let text: String = cmd.command_text.lines().next().unwrap_or("").chars().take(80).collect();
+        println!(
+            "  {} {} {} {}",
+            "│".dimmed(),
+            when.dimmed(),
+            cmd.session_id.chars().take(8).collect::<String>().dimmed(),
+            text
+        );
+    }
+    if commands.len() > PREVIEW {
+        println!(
+            "  {} {}",
+            "│".dimmed(),
+            format!("… and {} more", commands.len() - PREVIEW).dimmed()
+        );
+    }
+    println!("  {}", "─".repeat(RULE).dimmed());
+    println!(
+        "  {}",
+        "Summaries written from these commands are removed too.".dimmed()
+    );
+}
+
+/// Ask before deleting. Without a terminal to ask on, the answer is no:
+/// a script that wants this must say `--yes`.
+fn confirm(count: usize) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        bail!("Refusing to forget without confirmation; pass --yes to run non-interactively");
+    }
+    print!(
+        "\n  Forget {} {} for good? [y/N] ",
+        count,
+        plural(count, "command")
+    );
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Deleted rows also linger in FTS segments until they are merged, and in
+/// the WAL until it is checkpointed.
+fn scrub(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "INSERT INTO commands_fts(commands_fts) VALUES ('optimize');
+         INSERT INTO commands_trigram(commands_trigram) VALUES ('optimize');
+         INSERT INTO summaries_fts(summaries_fts) VALUES ('optimize');
+         PRAGMA wal_checkpoint(TRUNCATE);",
+    )
+    .context("Failed to scrub deleted history from the database file")
+}
+
+fn plural(n: usize, word: &str) -> String {
+    match (n, word) {
+        (1, _) => word.to_string(),
+        (_, "summary") => "summaries".to_string(),
+        _ => format!("{}s", word),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_bare_date_as_an_upper_bound_includes_the_whole_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        let request = |since: &str, until: &str| Request {
+            pattern: None,
+            ids: Vec::new(),
+            session: None,
+            since: Some(since.into()),
+            until: Some(until.into()),
+            yes: true,
+        };
+
+        let day = selection(&conn, &request("2024-03-01", "2024-03-01")).unwrap();
+        let (start, end) = (day.since.unwrap(), day.until.unwrap());
+        assert!(end - start >= 23 * 3_600_000);
+        let afternoon = selection(&conn, &request("2024-03-01", "2024-03-01 15:30")).unwrap();
+        assert!(start < afternoon.until.unwrap() && afternoon.until.unwrap() < end);
+        assert!(selection(&conn, &request("someday", "2024-03-01")).is_err());
+    }
+}
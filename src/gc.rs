@@ -0,0 +1,139 @@
+//! `recall gc` — apply `[retention]` limits and give the space back.
+//!
+//! The same clean-up also runs on its own, at most once a day, when `recall
+//! setup` or the TUI starts and a limit is configured: a retention policy
+//! nobody remembers to enforce is not much of a policy.
+
+use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+use rusqlite::Connection;
+
+use crate::ai::store;
+use crate::config::settings::{load_config, RetentionConfig};
+use crate::db::retention::{self, Reclaimed};
+
+const LAST_GC_KEY: &str = "last_gc";
+const AUTO_INTERVAL_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// An automatic run only rewrites the file when at least this share of it
+/// is free pages; `recall gc` always does.
+const AUTO_VACUUM_FREE_PERCENT: i64 = 20;
+
+const RULE: usize = 60;
+
+/// The outcome of one clean-up.
+pub struct Report {
+    pub reclaimed: Reclaimed,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+pub fn run() -> Result<()> {
+    let config = load_config()?;
+    let conn = crate::db::schema::open_db()?;
+
+    println!();
+    println!("  {} {}", "◉".cyan(), "Cleaning up".bold());
+    println!("  {}", "─".repeat(RULE).dimmed());
+    if !config.retention.is_set() {
+        println!(
+            "  {} {}",
+            "│".dimmed(),
+            "No [retention] limits set in config.toml; compacting only.".dimmed()
+        );
+    }
+
+    let report = collect(&conn, &config.retention, true)?;
+    print_report(&report, &config.retention);
+    println!("  {}", "─".repeat(RULE).dimmed());
+    println!(
+        "  {} {} {}",
+        "✓".green(),
+        format_bytes(report.bytes_before),
+        format!(
+            "→ {}  ({} reclaimed)",
+            format_bytes(report.bytes_after),
+            format_bytes((report.bytes_before - report.bytes_after).max(0))
+        )
+        .dimmed()
+    );
+    println!();
+    Ok(())
+}
+
+/// Run the clean-up if a limit is configured and it has not run in the last
+/// day. Returns what it did, or None when it was not due.
+pub fn run_if_due(conn: &Connection) -> Result<Option<Report>> {
+    let policy = load_config()?.retention;
+    if !policy.is_set() {
+        return Ok(None);
+    }
+    let now = Utc::now().timestamp_millis();
+    let last = store::get_meta(conn, LAST_GC_KEY)?
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(0);
+    if now - last < AUTO_INTERVAL_MS {
+        return Ok(None);
+    }
+    collect(conn, &policy, false).map(Some)
+}
+
+fn collect(conn: &Connection, policy: &RetentionConfig, always_vacuum: bool) -> Result<Report> {
+    let bytes_before = retention::file_bytes(conn)?;
+    let now = Utc::now().timestamp_millis();
+
+    let reclaimed = retention::apply(conn, policy, now)?;
+    retention::optimize(conn)?;
+
+    let free = bytes_before - retention::used_bytes(conn)?;
+    if always_vacuum || free * 100 > bytes_before * AUTO_VACUUM_FREE_PERCENT {
+        retention::vacuum(conn)?;
+    }
+
+    store::set_meta(conn, LAST_GC_KEY, &now.to_string())?;
+    Ok(Report {
+        reclaimed,
+        bytes_before,
+        bytes_after: retention::file_bytes(conn)?,
+    })
+}
+
+fn print_report(report: &Report, policy: &RetentionConfig) {
+    let r = &report.reclaimed;
+    let line = |text: String| println!("  {} {}", "│".dimmed(), text);
+
+    if r.outputs > 0 {
+        line(format!("Dropped output of {} commands", r.outputs.to_string().bold()));
+    }
+    if r.commands > 0 {
+        line(format!("Deleted {} commands", r.commands.to_string().bold()));
+    }
+    if r.sessions > 0 {
+        line(format!("Removed {} empty sessions", r.sessions.to_string().bold()));
+    }
+    if r.agent_chunks > 0 {
+        line(format!(
+            "Dropped {} indexed passages from old agent sessions",
+            r.agent_chunks.to_string().bold()
+        ));
+    }
+    if r.is_empty() && policy.is_set() {
+        line("Nothing is past its retention limit.".dimmed().to_string());
+    }
+    if r.over_cap {
+        line(format!(
+            "{} still over max_db_mb: what is left matches keep_patterns or is agent history",
+            "!".yellow()
+        ));
+    }
+}
+
+pub fn format_bytes(bytes: i64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{} KB", bytes / 1024)
+    }
+}
@@ -1,13 +1,19 @@
 mod ai;
 mod capture;
+mod audit;
 mod cli;
 mod config;
 mod db;
+mod embed;
+mod find;
+mod forget;
+mod gc;
 mod llm;
 mod privacy;
 mod search;
 mod setup;
 mod shell;
+mod summaries;
 mod tui;
 mod web;
 
@@ -25,24 +31,32 @@ async fn main() -> Result<()> {
     match cli.command {
         Some(Commands::Setup) => setup::run()?,
         Some(Commands::Init { shell }) => handle_init(&shell),
+        Some(Commands::Log {
+            end_session: true,
+            session,
+            ..
+        }) => capture::log::handle_end_session(&session)?,
         Some(Commands::Log {
             command,
             exit_code,
             start,
+            duration,
             cwd,
             session,
             terminal,
             output_file,
+            ..
         }) => {
-            capture::log::handle_log(
-                &command,
+            capture::log::handle_log(&capture::log::LogRecord {
+                command: command.unwrap_or_default(),
                 exit_code,
-                start,
-                cwd.as_deref(),
-                &session,
-                terminal.as_deref(),
-                output_file.as_deref(),
-            )?;
+                start_ms: start,
+                duration_ms: duration,
+                cwd,
+                session,
+                terminal,
+                output_file,
+            })?;
         }
         Some(Commands::SessionId) => handle_session_id(),
         Some(Commands::Search {
@@ -50,14 +64,72 @@ async fn main() -> Result<()> {
             repo,
             dir,
             failed,
+            since,
+            until,
             limit,
-        }) => handle_search(&query, repo, dir, failed, limit)?,
+            offset,
+            hybrid,
+            fuzzy,
+        }) => handle_search(search::engine::SearchOptions {
+            query,
+            repo,
+            dir,
+            failed_only: failed,
+            since: since.as_deref().map(search::dates::parse).transpose()?.map(|span| span.start),
+            until: until.as_deref().map(search::dates::parse).transpose()?.map(|span| span.end),
+            limit,
+            offset,
+            hybrid,
+            fuzzy,
+        })?,
+        Some(Commands::Find { query, limit }) => find::run(&query, limit)?,
         Some(Commands::Today) => handle_today()?,
+        Some(Commands::Week) => handle_week()?,
         Some(Commands::On { date }) => handle_on(&date)?,
         Some(Commands::Pause) => handle_pause()?,
         Some(Commands::Resume) => handle_resume()?,
+        Some(Commands::Incognito { off }) => handle_incognito(!off)?,
         Some(Commands::Summarize) => handle_summarize().await?,
+        Some(Commands::Summaries { tags: true, .. }) => summaries::run_labels()?,
+        Some(Commands::Summaries {
+            query,
+            tag,
+            intent,
+            since,
+            until,
+            limit,
+            tags: false,
+        }) => summaries::run(&summaries::Options {
+            query,
+            tag,
+            intent,
+            since,
+            until,
+            limit,
+        })?,
+        Some(Commands::Forget {
+            pattern,
+            id,
+            session,
+            since,
+            until,
+            yes,
+        }) => forget::run(forget::Request {
+            pattern,
+            ids: id,
+            session,
+            since,
+            until,
+            yes,
+        })?,
+        Some(Commands::Encrypt) => handle_encryption(true)?,
+        Some(Commands::Decrypt) => handle_encryption(false)?,
+        Some(Commands::Gc) => gc::run()?,
+        Some(Commands::Embed) => embed::run()?,
+        Some(Commands::Audit { yes }) => audit::run(yes)?,
         Some(Commands::Agents { command }) => ai::commands::handle(command)?,
+        Some(Commands::Import { command }) => ai::import::handle(command)?,
+        Some(Commands::Daemon) => capture::daemon::run()?,
         Some(Commands::Ui) => tui::app::run_tui()?,
         Some(Commands::Web { port }) => web::server::start_server(port).await?,
         None => {
@@ -75,11 +147,16 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn handle_init(shell: &str) {
-    match shell {
-        "zsh" => shell::zsh::print_hook(),
-        other => {
-            eprintln!("Unsupported shell: {}. Currently supported: zsh", other);
+fn handle_init(name: &str) {
+    match shell::Shell::parse(name) {
+        Some(shell) => shell.print_hook(),
+        None => {
+            let supported: Vec<&str> = shell::Shell::ALL.iter().map(|s| s.as_str()).collect();
+            eprintln!(
+                "Unsupported shell: {}. Currently supported: {}",
+                name,
+                supported.join(", ")
+            );
             std::process::exit(1);
         }
     }
@@ -89,64 +166,128 @@ fn handle_session_id() {
     println!("{}", uuid::Uuid::new_v4());
 }
 
-fn handle_search(
-    query: &str,
-    repo: Option<String>,
-    dir: Option<String>,
-    failed: bool,
-    limit: usize,
-) -> Result<()> {
+fn handle_search(mut opts: search::engine::SearchOptions) -> Result<()> {
     let conn = db::schema::open_db()?;
-    let opts = search::engine::SearchOptions {
-        query: query.to_string(),
-        repo,
-        dir,
-        failed_only: failed,
-        limit,
-    };
-    let results = search::engine::search(&conn, &opts)?;
+    let mut results = search::engine::search(&conn, &opts)?;
+    let mut found = summary_hits(&conn, &opts)?;
+    let mut heading = format!("Search: \"{}\"", opts.query);
+
+    // Nothing at all: perhaps a typo.
+    if results.is_empty() && found.is_empty() && opts.offset == 0 {
+        let query = search::query::parse(&opts.query)?;
+        match search::suggest::suggest(&conn, &opts.query, &query)? {
+            Some(correction) if config::settings::load_config()?.search.autocorrect => {
+                heading = format!("Search: \"{}\"  (nothing for \"{}\")", correction.input, opts.query);
+                opts.query = correction.input;
+                results = search::engine::search(&conn, &opts)?;
+                found = summary_hits(&conn, &opts)?;
+            }
+            Some(correction) => {
+                println!("\n  {} {}", "●".dimmed(), "No matching commands found.".dimmed());
+                println!(
+                    "  {} Did you mean {}?  {}",
+                    "●".yellow(),
+                    correction.words().bold(),
+                    format!("recall search \"{}\"", correction.input).dimmed()
+                );
+                println!();
+                return Ok(());
+            }
+            None => {}
+        }
+    }
 
-    if results.is_empty() {
-        println!("\n  {} {}\n", "●".dimmed(), "No matching commands found.".dimmed());
+    if results.is_empty() && found.is_empty() {
+        let note = if opts.offset > 0 {
+            "No more matching commands."
+        } else {
+            "No matching commands found."
+        };
+        println!("\n  {} {}\n", "●".dimmed(), note.dimmed());
         return Ok(());
     }
 
-    print_header(&format!("Search: \"{}\"", query), results.len());
+    print_header(&heading, results.len());
 
     let cmds: Vec<&db::models::Command> = results.iter().map(|r| &r.command).collect();
     print_commands_grouped(&cmds);
 
+    if !found.is_empty() {
+        println!(
+            "  {} {}  {}",
+            "✎".yellow(),
+            "Summaries".bold(),
+            format!("{} matching", found.len()).dimmed()
+        );
+        for hit in &found {
+            summaries::print_summary(&hit.summary);
+        }
+        println!();
+    }
+
+    // A full page means there may be more.
+    if results.len() == opts.limit {
+        println!(
+            "  {}",
+            format!("--offset {}  for the next page", opts.offset + opts.limit).dimmed()
+        );
+        println!();
+    }
+
     Ok(())
 }
 
-fn handle_today() -> Result<()> {
-    let conn = db::schema::open_db()?;
-    let commands = db::queries::get_commands_today(&conn)?;
-
-    if commands.is_empty() {
-        println!("\n  {} {}\n", "●".dimmed(), "No commands recorded today.".dimmed());
-        return Ok(());
+/// Summaries matching a command search, shown under its first page.
+fn summary_hits(
+    conn: &rusqlite::Connection,
+    opts: &search::engine::SearchOptions,
+) -> Result<Vec<db::models::SummarySearchResult>> {
+    const SUMMARY_HITS: usize = 3;
+    if opts.offset > 0 {
+        return Ok(Vec::new());
     }
+    search::engine::search_summaries(conn, opts, SUMMARY_HITS)
+}
 
+fn handle_today() -> Result<()> {
     let today = chrono::Local::now().format("%b %d, %Y").to_string();
-    print_header(&format!("Today — {}", today), commands.len());
-
-    let refs: Vec<&db::models::Command> = commands.iter().collect();
-    print_commands_grouped(&refs);
+    print_span(
+        &format!("Today — {}", today),
+        search::dates::parse("today")?,
+        "No commands recorded today.",
+    )
+}
 
-    Ok(())
+fn handle_week() -> Result<()> {
+    let week = search::dates::parse("this week")?;
+    let monday = chrono::DateTime::from_timestamp_millis(week.start)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%b %d").to_string())
+        .unwrap_or_default();
+    print_span(
+        &format!("This week — since {}", monday),
+        week,
+        "No commands recorded this week.",
+    )
 }
 
 fn handle_on(date: &str) -> Result<()> {
+    print_span(
+        date,
+        search::dates::parse_range(date)?,
+        &format!("No commands recorded on {}.", date),
+    )
+}
+
+fn print_span(title: &str, span: search::dates::Span, empty: &str) -> Result<()> {
     let conn = db::schema::open_db()?;
-    let commands = db::queries::get_commands_on_date(&conn, date)?;
+    let commands = db::queries::get_commands_between(&conn, span.start, span.end)?;
 
     if commands.is_empty() {
-        println!("\n  {} {}\n", "●".dimmed(), format!("No commands recorded on {}.", date).dimmed());
+        println!("\n  {} {}\n", "●".dimmed(), empty.dimmed());
         return Ok(());
     }
 
-    print_header(date, commands.len());
+    print_header(title, commands.len());
 
     let refs: Vec<&db::models::Command> = commands.iter().collect();
     print_commands_grouped(&refs);
@@ -172,12 +313,99 @@ fn handle_resume() -> Result<()> {
     Ok(())
 }
 
+fn handle_incognito(on: bool) -> Result<()> {
+    let session = match std::env::var("RECALL_SESSION_ID") {
+        Ok(id) if !id.is_empty() => id,
+        _ => anyhow::bail!(
+            "No recall session in this shell. Incognito applies to shells started with the recall hook."
+        ),
+    };
+    privacy::incognito::set_incognito(&session, on)?;
+    if on {
+        println!(
+            "\n  {} {}\n",
+            "◌".yellow(),
+            "Incognito: nothing from this shell is recorded until it exits or you run `recall incognito --off`."
+                .yellow()
+        );
+    } else {
+        println!("\n  {} {}\n", "▶".green(), "Recording this shell again.".green());
+    }
+    Ok(())
+}
+
+fn handle_encryption(encrypt: bool) -> Result<()> {
+    use db::crypto;
+
+    let path = config::settings::db_path();
+    let key = crypto::configured_key()?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "No key configured. Set {} in the environment or in ~/.recall/env, \
+             or set [database] key_command in config.toml",
+            crypto::KEY_ENV
+        )
+    })?;
+
+    if encrypt {
+        crypto::encrypt(&path, &key)?;
+        println!(
+            "\n  {} {}",
+            "✓".green(),
+            format!("Encrypted {}", path.display()).green()
+        );
+        println!(
+            "  {}\n",
+            "Keep the key somewhere safe: without it the history cannot be read.".dimmed()
+        );
+    } else {
+        crypto::decrypt(&path, &key)?;
+        println!(
+            "\n  {} {}",
+            "✓".green(),
+            format!("Decrypted {}", path.display()).green()
+        );
+        println!(
+            "  {}\n",
+            format!(
+                "Remove {} or key_command too, or a fresh database would be created encrypted.",
+                crypto::KEY_ENV
+            )
+            .dimmed()
+        );
+    }
+    Ok(())
+}
+
 async fn handle_summarize() -> Result<()> {
     let cfg = config::settings::load_config()?;
     let conn = db::schema::open_db()?;
 
-    let session_ids = db::queries::get_unsummarized_sessions(&conn, 3)?;
-    if session_ids.is_empty() {
+    const MIN_COMMANDS: usize = 3;
+    let gap = cfg.sessions.idle_gap_ms();
+    let now = Utc::now().timestamp_millis();
+
+    // Each finished work session gets its own summary; one still in progress
+    // waits until its shell exits or goes idle.
+    let mut pending: Vec<(String, Vec<db::models::Command>)> = Vec::new();
+    for session_id in db::queries::get_unsummarized_sessions(&conn, MIN_COMMANDS)? {
+        let ended = db::queries::get_session(&conn, &session_id)?
+            .is_some_and(|s| s.end_time.is_some());
+        let covered = db::queries::last_summarized_at(&conn, &session_id)?.unwrap_or(-1);
+        let commands: Vec<db::models::Command> =
+            db::queries::get_session_commands(&conn, &session_id)?
+                .into_iter()
+                .filter(|c| c.timestamp > covered)
+                .collect();
+        for work in db::work_sessions::split(&commands, gap) {
+            if work.len() >= MIN_COMMANDS
+                && !db::work_sessions::is_open(work, ended, gap, now)
+            {
+                pending.push((session_id.clone(), work.to_vec()));
+            }
+        }
+    }
+
+    if pending.is_empty() {
         println!("\n  {} {}\n", "●".dimmed(), "No sessions to summarize.".dimmed());
         return Ok(());
     }
@@ -186,19 +414,24 @@ async fn handle_summarize() -> Result<()> {
     println!(
         "  {} Summarizing {} sessions...",
         "◉".cyan(),
-        session_ids.len().to_string().bold()
+        pending.len().to_string().bold()
     );
     println!("  {}", "─".repeat(60).dimmed());
 
-    for session_id in &session_ids {
-        let commands = db::queries::get_session_commands(&conn, session_id)?;
+    for (session_id, commands) in &pending {
+        let started_at = commands.first().map(|c| c.timestamp);
         print!(
-            "  {} Session {} ",
+            "  {} Session {} {} ",
             "│".dimmed(),
-            session_id[..8].cyan()
+            session_id[..8].cyan(),
+            started_at
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .map(|dt| dt.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+                .unwrap_or_default()
+                .dimmed()
         );
 
-        match llm::summarizer::summarize_session(&cfg.llm, &commands).await {
+        match llm::summarizer::summarize_session(&cfg.llm, commands).await {
             Ok((summary_text, tags, intent)) => {
                 let summary = db::models::Summary {
                     id: None,
@@ -207,6 +440,8 @@ async fn handle_summarize() -> Result<()> {
                     tags: Some(tags),
                     intent: Some(intent.clone()),
                     created_at: Utc::now().timestamp_millis(),
+                    started_at,
+                    ended_at: db::work_sessions::finished_at(commands),
                 };
                 db::queries::insert_summary(&conn, &summary)?;
                 println!("{}", "✓".green());
@@ -256,7 +491,7 @@ async fn handle_ask(question: &str) -> Result<()> {
 
     candidates.sort_by_key(|c| c.id);
     candidates.dedup_by_key(|c| c.id);
-    candidates.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
     candidates.truncate(100);
 
     println!();
@@ -294,19 +529,14 @@ fn print_header(title: &str, count: usize) {
 }
 
 fn print_commands_grouped(commands: &[&db::models::Command]) {
-    // Group by session
-    let mut groups: Vec<(&str, Vec<&db::models::Command>)> = Vec::new();
-    for cmd in commands {
-        if let Some(last) = groups.last_mut() {
-            if last.0 == cmd.session_id.as_str() {
-                last.1.push(cmd);
-                continue;
-            }
-        }
-        groups.push((&cmd.session_id, vec![cmd]));
-    }
-
-    for (session_id, cmds) in &groups {
+    // Group by work session, so a terminal open all day reads as the separate
+    // stretches of work it held.
+    let idle_gap_ms = config::settings::load_config()
+        .map(|c| c.sessions.idle_gap_ms())
+        .unwrap_or_else(|_| config::settings::SessionsConfig::default().idle_gap_ms());
+
+    for cmds in db::work_sessions::split(commands, idle_gap_ms) {
+        let session_id = cmds[0].session_id.as_str();
         // Session header
         let first_ts = cmds
             .first()
@@ -1,6 +1,11 @@
 /// Check if a command matches any ignore pattern.
 /// Patterns support simple glob-style matching with `*` as wildcard.
 pub fn should_ignore(command: &str, patterns: &[String]) -> bool {
+    matches_any(command, patterns)
+}
+
+/// Case-insensitive glob match against a list of patterns.
+pub fn matches_any(command: &str, patterns: &[String]) -> bool {
     let cmd_upper = command.to_uppercase();
     for pattern in patterns {
         if glob_match(&cmd_upper, &pattern.to_uppercase()) {
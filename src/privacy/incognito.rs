@@ -0,0 +1,81 @@
+//! Places and shells where nothing is recorded.
+//!
+//! `recall pause` stops every terminal at once. These are narrower: one shell
+//! gone incognito, marked by a file named after its session id, and
+//! directory trees that are never recorded, either listed in config or
+//! marked with a `.recallignore` file at their root.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::settings::incognito_dir;
+
+/// A file of this name in a directory or any parent keeps that tree's
+/// commands out of the history.
+pub const IGNORE_FILE: &str = ".recallignore";
+
+fn marker(session_id: &str) -> PathBuf {
+    // Session ids are UUIDs; anything else must not become a path.
+    let name: String = session_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    incognito_dir().join(name)
+}
+
+pub fn is_incognito(session_id: &str) -> bool {
+    !session_id.is_empty() && marker(session_id).exists()
+}
+
+pub fn set_incognito(session_id: &str, on: bool) -> Result<()> {
+    let path = marker(session_id);
+    if on {
+        std::fs::create_dir_all(incognito_dir())?;
+        std::fs::write(&path, "")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    } else if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+/// Whether commands run in `cwd` are kept out of the history: it lies under
+/// one of `ignore_dirs` (where `~` means the home directory), or under a
+/// directory holding a `.recallignore`.
+pub fn is_ignored_dir(cwd: &Path, ignore_dirs: &[String]) -> bool {
+    let home = dirs::home_dir().unwrap_or_default();
+    let listed = ignore_dirs.iter().filter(|dir| !dir.trim().is_empty()).any(|dir| {
+        let dir = match dir.strip_prefix('~') {
+            Some(rest) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(dir),
+        };
+        cwd.starts_with(&dir)
+    });
+    listed || cwd.ancestors().any(|dir| dir.join(IGNORE_FILE).exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_listed_directory_covers_everything_beneath_it() {
+        let dirs = vec!["/work/client-x".to_string()];
+        assert!(is_ignored_dir(Path::new("/work/client-x"), &dirs));
+        assert!(is_ignored_dir(Path::new("/work/client-x/src/api"), &dirs));
+        assert!(!is_ignored_dir(Path::new("/work/client-xy"), &dirs));
+        assert!(!is_ignored_dir(Path::new("/work"), &dirs));
+    }
+
+    #[test]
+    fn a_recallignore_file_marks_its_whole_tree() {
+        let root = std::env::temp_dir().join(format!("recall-ignore-{}", std::process::id()));
+        let nested = root.join("secret").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("secret").join(IGNORE_FILE), "").unwrap();
+
+        assert!(is_ignored_dir(&nested, &[]));
+        assert!(!is_ignored_dir(&root, &[]));
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
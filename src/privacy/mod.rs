@@ -1 +1,3 @@
 pub mod filter;
+pub mod incognito;
+pub mod redact;
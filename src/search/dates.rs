@@ -0,0 +1,255 @@
+//! Dates as people type them: `2026-09-01`, `yesterday`, `last monday`,
+//! `3 days ago`, `this week`.
+//!
+//! Every phrase names a span of local time. A lower bound (`--since`,
+//! `after:`) takes its start and an upper bound (`--until`) its end, so
+//! `--since monday --until monday` is all of Monday. A phrase with a time of
+//! day, or counted in hours, names an instant.
+
+use anyhow::{bail, Result};
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeDelta, TimeZone,
+    Weekday,
+};
+
+/// A stretch of local time in epoch milliseconds, `start` inclusive and
+/// `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// The span a phrase names, relative to now.
+pub fn parse(text: &str) -> Result<Span> {
+    parse_at(text, Local::now())
+}
+
+/// Either one phrase or a `from..to` range, for `recall on`.
+pub fn parse_range(text: &str) -> Result<Span> {
+    match text.split_once("..") {
+        Some((from, to)) => {
+            let (from, to) = (parse(from)?, parse(to)?);
+            if to.end <= from.start {
+                bail!("'{}' ends before it starts", text);
+            }
+            Ok(Span {
+                start: from.start,
+                end: to.end,
+            })
+        }
+        None => parse(text),
+    }
+}
+
+pub fn parse_at(text: &str, now: DateTime<Local>) -> Result<Span> {
+    let phrase = text.trim().to_ascii_lowercase();
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    match span_of(&phrase, now) {
+        Some(span) => Ok(span),
+        None => bail!(
+            "Unrecognised date '{}': try 2026-09-01, 2026-09-01 14:30, yesterday, \
+             last monday, 3 days ago or this week",
+            text.trim()
+        ),
+    }
+}
+
+fn span_of(phrase: &str, now: DateTime<Local>) -> Option<Span> {
+    let today = now.date_naive();
+
+    if let Ok(date) = NaiveDate::parse_from_str(phrase, "%Y-%m-%d") {
+        return days(date, 1);
+    }
+    if let Some(instant) = ["%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dt%H:%M:%S"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(phrase, f).ok())
+    {
+        return instant_at(local_ms(instant)?);
+    }
+    if let Ok(first) = NaiveDate::parse_from_str(&format!("{}-01", phrase), "%Y-%m-%d") {
+        return month(first);
+    }
+
+    match phrase {
+        "now" => return instant_at(now.timestamp_millis()),
+        "today" => return days(today, 1),
+        "yesterday" => return days(today.pred_opt()?, 1),
+        "this week" => return days(week_start(today), 7),
+        "last week" => return days(week_start(today) - Duration::days(7), 7),
+        "this month" => return month(today.with_day(1)?),
+        "last month" => return month(today.with_day(1)?.checked_sub_months(Months::new(1))?),
+        "this year" => return year(today.year()),
+        "last year" => return year(today.year() - 1),
+        _ => {}
+    }
+
+    // monday: the latest one up to today. last monday: the one before today.
+    if let Some(name) = phrase.strip_prefix("last ") {
+        let weekday = name.parse::<Weekday>().ok()?;
+        let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        let back = if back == 0 { 7 } else { back };
+        return days(today - Duration::days(back as i64), 1);
+    }
+    if let Ok(weekday) = phrase.parse::<Weekday>() {
+        let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        return days(today - Duration::days(back as i64), 1);
+    }
+
+    ago(phrase, now)
+}
+
+/// `3 days ago`, `an hour ago`, `2 weeks ago`, and the short `5h`, `7d`, `2w`.
+fn ago(phrase: &str, now: DateTime<Local>) -> Option<Span> {
+    let (count, unit) = match phrase.strip_suffix(" ago") {
+        Some(rest) => {
+            let (count, unit) = rest.split_once(' ')?;
+            let count = match count {
+                "a" | "an" | "one" => 1,
+                n => n.parse().ok()?,
+            };
+            (count, unit.trim_end_matches('s'))
+        }
+        None => {
+            let (last, _) = phrase.char_indices().next_back()?;
+            let (count, unit) = phrase.split_at(last);
+            (count.parse::<i64>().ok()?, unit)
+        }
+    };
+
+    // A count too big for the calendar is no date at all, not a panic.
+    let today = now.date_naive();
+    match unit {
+        "minute" | "min" => {
+            instant_at(now.checked_sub_signed(TimeDelta::try_minutes(count)?)?.timestamp_millis())
+        }
+        "hour" | "h" => {
+            instant_at(now.checked_sub_signed(TimeDelta::try_hours(count)?)?.timestamp_millis())
+        }
+        "day" | "d" => days(today.checked_sub_signed(TimeDelta::try_days(count)?)?, 1),
+        "week" | "w" => days(today.checked_sub_signed(TimeDelta::try_weeks(count)?)?, 1),
+        "month" => days(today.checked_sub_months(Months::new(count.try_into().ok()?))?, 1),
+        _ => None,
+    }
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+fn days(first: NaiveDate, count: i64) -> Option<Span> {
+    Some(Span {
+        start: midnight(first)?,
+        end: midnight(first.checked_add_signed(Duration::days(count))?)?,
+    })
+}
+
+fn month(first: NaiveDate) -> Option<Span> {
+    Some(Span {
+        start: midnight(first)?,
+        end: midnight(first.checked_add_months(Months::new(1))?)?,
+    })
+}
+
+fn year(year: i32) -> Option<Span> {
+    Some(Span {
+        start: midnight(NaiveDate::from_ymd_opt(year, 1, 1)?)?,
+        end: midnight(NaiveDate::from_ymd_opt(year + 1, 1, 1)?)?,
+    })
+}
+
+fn instant_at(ms: i64) -> Option<Span> {
+    Some(Span { start: ms, end: ms })
+}
+
+fn midnight(day: NaiveDate) -> Option<i64> {
+    local_ms(day.and_hms_opt(0, 0, 0)?)
+}
+
+/// A local wall-clock time, taking the earlier reading when a DST change
+/// repeats it and the moment after when one skips it.
+fn local_ms(naive: NaiveDateTime) -> Option<i64> {
+    match Local.from_local_datetime(&naive).earliest() {
+        Some(local) => Some(local.timestamp_millis()),
+        None => Local
+            .from_local_datetime(&(naive + Duration::hours(1)))
+            .earliest()
+            .map(|local| local.timestamp_millis()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Friday 2026-10-16, mid-afternoon.
+    fn friday() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 16, 15, 0, 0).unwrap()
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> i64 {
+        midnight(NaiveDate::from_ymd_opt(y, m, d).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn a_date_is_the_whole_day() {
+        let span = parse_at("2024-03-01", friday()).unwrap();
+        assert_eq!(span, Span { start: day(2024, 3, 1), end: day(2024, 3, 2) });
+        let afternoon = parse_at("2024-03-01 15:30", friday()).unwrap();
+        assert!(span.start < afternoon.start && afternoon.end < span.end);
+    }
+
+    #[test]
+    fn relative_days_count_back_from_today() {
+        let now = friday();
+        assert_eq!(parse_at("yesterday", now).unwrap().start, day(2026, 10, 15));
+        assert_eq!(parse_at("3 days ago", now).unwrap().start, day(2026, 10, 13));
+        assert_eq!(parse_at("7d", now).unwrap().start, day(2026, 10, 9));
+        assert_eq!(parse_at("monday", now).unwrap().start, day(2026, 10, 12));
+        assert_eq!(parse_at("Last  Friday", now).unwrap().start, day(2026, 10, 9));
+        assert_eq!(parse_at("friday", now).unwrap().start, day(2026, 10, 16));
+        let hours = parse_at("2 hours ago", now).unwrap();
+        assert_eq!(hours.start, now.timestamp_millis() - 2 * 3_600_000);
+    }
+
+    #[test]
+    fn weeks_and_months_are_calendar_periods() {
+        let now = friday();
+        let week = parse_at("this week", now).unwrap();
+        assert_eq!(week, Span { start: day(2026, 10, 12), end: day(2026, 10, 19) });
+        assert_eq!(parse_at("last week", now).unwrap().end, week.start);
+        let month = parse_at("last month", now).unwrap();
+        assert_eq!(month, Span { start: day(2026, 9, 1), end: day(2026, 10, 1) });
+        assert_eq!(parse_at("2026-09", now).unwrap(), month);
+    }
+
+    #[test]
+    fn ranges_run_from_the_first_start_to_the_last_end() {
+        let range = parse_range("2026-09-01..2026-09-07").unwrap();
+        assert_eq!(range, Span { start: day(2026, 9, 1), end: day(2026, 9, 8) });
+        assert!(parse_range("2026-09-07..2026-09-01").is_err());
+    }
+
+    #[test]
+    fn nonsense_is_an_error() {
+        assert!(parse_at("someday", friday()).is_err());
+        assert!(parse_at("last fortnight", friday()).is_err());
+        assert!(parse_at("", friday()).is_err());
+    }
+
+    #[test]
+    fn a_phrase_ending_in_a_wide_character_is_an_error() {
+        assert!(parse_at("3日", friday()).is_err());
+        assert!(parse_at("é", friday()).is_err());
+        assert!(parse_at("2 semaines passé", friday()).is_err());
+    }
+
+    #[test]
+    fn counts_past_the_calendar_are_an_error() {
+        let huge = ["99999999999999d", "99999999999999w", "9223372036854775807h", "99999999999999 minutes ago"];
+        for phrase in huge {
+            assert!(parse_at(phrase, friday()).is_err(), "{}", phrase);
+        }
+        assert!(parse_at("-9223372036854775808d", friday()).is_err());
+    }
+}
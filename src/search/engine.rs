@@ -1,15 +1,30 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
+use std::collections::HashMap;
+
 use crate::db::models::{Command, SearchResult, SummarySearchResult};
 use crate::db::queries;
+use crate::embed::{self, Embedder};
+
+use super::query::{self, Exit, Query};
 
 pub struct SearchOptions {
     pub query: String,
     pub repo: Option<String>,
     pub dir: Option<String>,
     pub failed_only: bool,
+    /// Epoch milliseconds, inclusive.
+    pub since: Option<i64>,
+    /// Epoch milliseconds, exclusive.
+    pub until: Option<i64>,
     pub limit: usize,
+    pub offset: usize,
+    /// Blend in commands near the query's meaning, from the local embedding
+    /// model.
+    pub hybrid: bool,
+    /// Match the text anywhere in a command, mid-word included.
+    pub fuzzy: bool,
 }
 
 impl Default for SearchOptions {
@@ -19,56 +34,240 @@ impl Default for SearchOptions {
             repo: None,
             dir: None,
             failed_only: false,
+            since: None,
+            until: None,
             limit: 50,
+            offset: 0,
+            hybrid: false,
+            fuzzy: false,
         }
     }
 }
 
-/// Search commands using FTS5, with optional structured filters applied post-query.
+/// Search commands. `opts.query` is in the search language of
+/// [`super::query`]; the `--repo`, `--dir`, `--failed`, `--since` and
+/// `--until` flags add to it.
 pub fn search(conn: &Connection, opts: &SearchOptions) -> Result<Vec<SearchResult>> {
-    let mut results = queries::search_commands(conn, &opts.query, opts.limit * 2)?;
+    let query = parse(opts)?;
+    if opts.hybrid && query.has_text() {
+        let embedder = embed::configured()?;
+        return search_hybrid(conn, &query, embedder.as_ref(), opts.limit, opts.offset);
+    }
+    if opts.fuzzy {
+        return queries::find_commands_fuzzy(conn, &query, opts.limit, opts.offset);
+    }
+    queries::find_commands(conn, &query, opts.limit, opts.offset)
+}
 
-    // Apply structured filters
-    results.retain(|r| {
-        if opts.failed_only && r.command.exit_code != Some(0) && r.command.exit_code.is_none() {
-            // Keep commands with non-zero exit codes
-        }
-        if opts.failed_only {
-            if let Some(code) = r.command.exit_code {
-                if code == 0 {
-                    return false;
-                }
-            } else {
-                return false;
-            }
-        }
-        if let Some(ref repo) = opts.repo {
-            if r.command.git_repo.as_deref() != Some(repo.as_str()) {
-                return false;
-            }
-        }
-        if let Some(ref dir) = opts.dir {
-            if let Some(ref cwd) = r.command.cwd {
-                if !cwd.contains(dir.as_str()) {
-                    return false;
-                }
-            } else {
-                return false;
-            }
-        }
-        true
-    });
+/// The query with the flags folded in.
+fn parse(opts: &SearchOptions) -> Result<Query> {
+    let mut query = query::parse(&opts.query)?;
+    if let Some(repo) = &opts.repo {
+        query.repo = Some(repo.clone());
+    }
+    if let Some(dir) = &opts.dir {
+        query.dir = Some(dir.clone());
+    }
+    if opts.failed_only {
+        query.exit = Some(Exit::IsNot(0));
+    }
+    if opts.since.is_some() {
+        query.after = opts.since;
+    }
+    if opts.until.is_some() {
+        query.before = opts.until;
+    }
+    Ok(query)
+}
+
+/// Commands that match the words or the meaning of the query, the ones that
+/// do both first. Pages are cut from the blended list, so each page asks for
+/// everything before it too.
+pub fn search_hybrid(
+    conn: &Connection,
+    query: &Query,
+    embedder: &dyn Embedder,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<SearchResult>> {
+    let depth = offset + limit;
+    let words = queries::find_commands(conn, query, depth, 0)?;
+    let vector = embed::embed_query(embedder, &query.text())?;
+    let near = embed::store::nearest_commands(conn, embedder.model(), &vector, query, depth)?;
+
+    let ranked = embed::blend(&[
+        words.iter().filter_map(|hit| hit.command.id).collect(),
+        near.iter().filter_map(|hit| hit.command.id).collect(),
+    ]);
+    let mut hits: HashMap<i64, SearchResult> = words
+        .into_iter()
+        .chain(near)
+        .filter_map(|hit| Some((hit.command.id?, hit)))
+        .collect();
 
-    results.truncate(opts.limit);
-    Ok(results)
+    Ok(ranked
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|(id, score)| {
+            let mut hit = hits.remove(&id)?;
+            hit.rank = -score;
+            Some(hit)
+        })
+        .collect())
 }
 
-/// Search summaries using FTS5.
-pub fn search_summaries(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SummarySearchResult>> {
-    queries::search_summaries(conn, query, limit)
+/// The summaries a command search also matches, best first. Only free text
+/// finds them: a search that is all filters is after commands.
+pub fn search_summaries(conn: &Connection, opts: &SearchOptions, limit: usize) -> Result<Vec<SummarySearchResult>> {
+    let query = parse(opts)?;
+    if !query.has_text() {
+        return Ok(Vec::new());
+    }
+    queries::find_summaries(conn, &query, limit)
 }
 
 /// Get recent commands (for LLM context building).
 pub fn get_recent_commands(conn: &Connection, limit: usize) -> Result<Vec<Command>> {
     queries::get_all_commands(conn, limit)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Session;
+
+    fn db_with_mostly_passing_tests() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        queries::insert_session(
+            &conn,
+            &Session {
+                id: "s".into(),
+                start_time: 0,
+                end_time: None,
+                terminal_app: None,
+                initial_dir: None,
+            },
+        )
+        .unwrap();
+        // Forty passing runs for every failing one.
+        for i in 0..205 {
+            queries::insert_command(
+                &conn,
+                &Command {
+                    id: None,
+                    session_id: "s".into(),
+                    command_text: "cargo test".into(),
+                    timestamp: i,
+                    duration_ms: None,
+                    cwd: None,
+                    git_repo: Some("api".into()),
+                    git_branch: None,
+                    exit_code: Some(if i % 41 == 0 { 101 } else { 0 }),
+                    output: None,
+                },
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn filters_do_not_eat_into_the_limit() {
+        let conn = db_with_mostly_passing_tests();
+        let opts = SearchOptions {
+            query: "test".into(),
+            failed_only: true,
+            limit: 4,
+            ..Default::default()
+        };
+        let results = search(&conn, &opts).unwrap();
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.command.exit_code == Some(101)));
+    }
+
+    #[test]
+    fn a_command_search_also_finds_summaries_by_their_words() {
+        let conn = db_with_mostly_passing_tests();
+        queries::insert_summary(
+            &conn,
+            &crate::db::models::Summary {
+                id: None,
+                session_id: "s".into(),
+                summary_text: "Chased a flaky test in the api".into(),
+                tags: Some(r#"["rust"]"#.into()),
+                intent: Some("debugging".into()),
+                created_at: 300,
+                started_at: Some(0),
+                ended_at: Some(204),
+            },
+        )
+        .unwrap();
+
+        let hits = |query: &str| {
+            let opts = SearchOptions {
+                query: query.into(),
+                ..Default::default()
+            };
+            search_summaries(&conn, &opts, 3).unwrap().len()
+        };
+        assert_eq!(hits("flaky"), 1);
+        assert_eq!(hits("flaky tag:rust"), 1);
+        assert_eq!(hits("flaky repo:api"), 0, "summaries have no repo");
+        assert_eq!(hits("tag:rust"), 0, "a search of filters alone is after commands");
+    }
+
+    #[test]
+    fn pages_follow_on_without_overlap() {
+        let conn = db_with_mostly_passing_tests();
+        let page = |offset| {
+            let opts = SearchOptions {
+                query: "repo:api".into(),
+                limit: 100,
+                offset,
+                ..Default::default()
+            };
+            search(&conn, &opts)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.command.timestamp)
+                .collect::<Vec<_>>()
+        };
+        let (first, second, third) = (page(0), page(100), page(200));
+        assert_eq!((first.len(), second.len(), third.len()), (100, 100, 5));
+        assert_eq!(first[0], 204, "newest first");
+        assert_eq!(first[99] - 1, second[0]);
+        assert_eq!(third[4], 0);
+    }
+
+    #[test]
+    fn hybrid_search_finds_commands_by_meaning() {
+        let conn = db_with_mostly_passing_tests();
+        queries::insert_command(
+            &conn,
+            &Command {
+                id: None,
+                session_id: "s".into(),
+                command_text: "kubectl rollout restart api".into(),
+                timestamp: 300,
+                duration_ms: None,
+                cwd: None,
+                git_repo: Some("api".into()),
+                git_branch: None,
+                exit_code: Some(0),
+                output: None,
+            },
+        )
+        .unwrap();
+        embed::backfill(&conn, &embed::tests::Synonyms, 64, |_| {}).unwrap();
+
+        let query = query::parse("deploy repo:api").unwrap();
+        assert!(queries::find_commands(&conn, &query, 10, 0).unwrap().is_empty());
+        let hits = search_hybrid(&conn, &query, &embed::tests::Synonyms, 10, 0).unwrap();
+        let texts: Vec<&str> = hits.iter().map(|hit| hit.command.command_text.as_str()).collect();
+        // Unrelated commands are not near just because the filters let them through.
+        assert_eq!(texts, vec!["kubectl rollout restart api"]);
+        assert!(search_hybrid(&conn, &query, &embed::tests::Synonyms, 10, 1).unwrap().is_empty());
+    }
+}
@@ -1 +1,5 @@
+pub mod dates;
 pub mod engine;
+pub mod query;
+pub mod suggest;
+pub mod unified;
@@ -0,0 +1,665 @@
+//! The search language shared by `recall search`, `recall agents search`, the
+//! TUI search box and the web API.
+//!
+//! Free text is matched with FTS5. `key:value` terms narrow by structured
+//! fields and compile to SQL predicates:
+//!
+//! ```text
+//! repo:api branch:main exit:!0 dir:~/src after:2026-09-01 before:yesterday
+//! until:"last monday" took:>30s source:codex project:recall model:opus "quoted phrase" -excluded
+//! tag:rust intent:debugging
+//! ```
+//!
+//! Some keys only mean something for one kind of result — `exit:` for shell
+//! commands, `model:` for agent sessions, `tag:` for summaries — and a query
+//! using one rules the other kinds out rather than silently ignoring the key.
+
+use anyhow::{bail, Result};
+use rusqlite::types::Value as SqlValue;
+
+use super::dates;
+use crate::ai::models::Source;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Is(i32),
+    IsNot(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    fn sql(&self) -> &'static str {
+        match self {
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Gt => ">",
+            Cmp::Ge => ">=",
+        }
+    }
+}
+
+/// What `source:` asked for: shell history, or one agent tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Shell,
+    Agent(Source),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    /// Words and quoted phrases, all of which must match.
+    pub terms: Vec<String>,
+    /// Words and phrases that must not appear.
+    pub excluded: Vec<String>,
+    pub repo: Option<String>,
+    pub branch: Option<String>,
+    pub exit: Option<Exit>,
+    pub dir: Option<String>,
+    /// Epoch milliseconds, inclusive.
+    pub after: Option<i64>,
+    /// Epoch milliseconds, exclusive.
+    pub before: Option<i64>,
+    /// Duration comparison, in milliseconds.
+    pub took: Option<(Cmp, i64)>,
+    pub source: Option<Scope>,
+    pub project: Option<String>,
+    pub model: Option<String>,
+    /// One of a summary's tags, any case.
+    pub tag: Option<String>,
+    /// A summary's intent, any case.
+    pub intent: Option<String>,
+}
+
+/// Parse a search box. Unknown `key:` prefixes are ordinary text (URLs and
+/// `host:port` are common in commands); a known key with a bad value is an
+/// error, and one with no value yet, as while typing, is ignored.
+pub fn parse(input: &str) -> Result<Query> {
+    let mut query = Query::default();
+
+    for token in tokenize(input) {
+        if token.negated {
+            if !token.text.is_empty() {
+                query.excluded.push(token.text);
+            }
+            continue;
+        }
+        let filter = if token.quoted {
+            None
+        } else {
+            token
+                .text
+                .split_once(':')
+                .filter(|(key, _)| KEYS.contains(&key.to_ascii_lowercase().as_str()))
+        };
+        match filter {
+            Some((key, value)) => {
+                let value = token.value.as_deref().unwrap_or(value).trim();
+                if !value.is_empty() {
+                    apply(&mut query, &key.to_ascii_lowercase(), value)?;
+                }
+            }
+            None if !token.text.is_empty() => query.terms.push(token.text),
+            None => {}
+        }
+    }
+
+    Ok(query)
+}
+
+const KEYS: &[&str] = &[
+    "repo", "branch", "exit", "dir", "after", "before", "since", "until", "took", "source",
+    "project", "model", "tag", "intent",
+];
+
+fn apply(query: &mut Query, key: &str, value: &str) -> Result<()> {
+    match key {
+        "repo" => query.repo = Some(value.to_string()),
+        "branch" => query.branch = Some(value.to_string()),
+        "dir" => query.dir = Some(value.to_string()),
+        "project" => query.project = Some(value.to_string()),
+        "model" => query.model = Some(value.to_string()),
+        "tag" => query.tag = Some(value.to_string()),
+        "intent" => query.intent = Some(value.to_string()),
+        "exit" => {
+            let (negated, code) = match value.strip_prefix('!') {
+                Some(code) => (true, code),
+                None => (false, value),
+            };
+            let Ok(code) = code.parse::<i32>() else {
+                bail!("exit:{} — expected an exit code like exit:0 or exit:!0", value);
+            };
+            query.exit = Some(if negated { Exit::IsNot(code) } else { Exit::Is(code) });
+        }
+        // after: and since: include the day named, before: excludes it and
+        // until: runs to its end.
+        "after" | "since" => query.after = Some(parse_date(key, value)?.start),
+        "before" => query.before = Some(parse_date(key, value)?.start),
+        "until" => query.before = Some(parse_date(key, value)?.end),
+        "took" => query.took = Some(parse_took(value)?),
+        "source" => {
+            query.source = Some(match value.to_ascii_lowercase().as_str() {
+                "shell" | "commands" => Scope::Shell,
+                other => match Source::parse(other) {
+                    Some(source) => Scope::Agent(source),
+                    None => {
+                        let names: Vec<&str> = Source::all().iter().map(|s| s.as_str()).collect();
+                        bail!("source:{} — expected shell or one of: {}", value, names.join(", "))
+                    }
+                },
+            })
+        }
+        _ => unreachable!("every key in KEYS is handled"),
+    }
+    Ok(())
+}
+
+/// A date phrase for `key`, in the language of [`super::dates`].
+fn parse_date(key: &str, value: &str) -> Result<dates::Span> {
+    dates::parse(value).map_err(|_| {
+        anyhow::anyhow!(
+            "{}:{} — expected a date like 2026-09-01, yesterday, \"last monday\" or 7d",
+            key,
+            value
+        )
+    })
+}
+
+/// `>30s`, `<=2m`, `500ms`, `1h`. A bare duration means at least that long.
+fn parse_took(value: &str) -> Result<(Cmp, i64)> {
+    let (cmp, rest) = if let Some(rest) = value.strip_prefix(">=") {
+        (Cmp::Ge, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (Cmp::Le, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (Cmp::Gt, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (Cmp::Lt, rest)
+    } else {
+        (Cmp::Ge, value)
+    };
+
+    let split = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+    let (number, unit) = rest.split_at(split);
+    let scale = match unit {
+        "ms" => 1.0,
+        "" | "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        _ => 0.0,
+    };
+    match number.parse::<f64>() {
+        Ok(n) if scale > 0.0 => Ok((cmp, (n * scale) as i64)),
+        _ => bail!("took:{} — expected a duration like took:>30s, took:<500ms or took:>2m", value),
+    }
+}
+
+struct Token {
+    text: String,
+    /// For `key:"a value"`, the unquoted value.
+    value: Option<String>,
+    quoted: bool,
+    negated: bool,
+}
+
+/// Split on whitespace, keeping double-quoted runs together. A leading `-`
+/// negates, except on a `--flag`; a quote straight after `key:` quotes the
+/// value.
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut negated = false;
+        if c == '-' {
+            let mut ahead = chars.clone();
+            ahead.next();
+            match ahead.peek() {
+                // `--resume` is a flag to look for, not an exclusion.
+                Some('-') => {}
+                Some(next) if !next.is_whitespace() => {
+                    chars.next();
+                    negated = true;
+                }
+                // A lone `-` is nothing to search for.
+                _ => {
+                    chars.next();
+                    continue;
+                }
+            }
+        }
+
+        let mut text = String::new();
+        let mut value = None;
+        let mut quoted = false;
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            quoted = true;
+            text = read_quoted(&mut chars);
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                chars.next();
+                if c == '"' && text.ends_with(':') {
+                    value = Some(read_quoted(&mut chars));
+                    break;
+                }
+                text.push(c);
+            }
+        }
+
+        tokens.push(Token {
+            text,
+            value,
+            quoted,
+            negated,
+        });
+    }
+
+    tokens
+}
+
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut text = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            break;
+        }
+        text.push(c);
+    }
+    text.trim().to_string()
+}
+
+/// Quote a term for FTS5 so `-`, `/`, `.` and `:` are literals, not query
+/// syntax. Several words in one term become a phrase.
+fn fts_phrase(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+/// A `LIKE` pattern matching `value` literally, with `*` as a wildcard.
+fn like_pattern(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+        .replace('*', "%")
+}
+
+/// Expand a leading `~` to the home directory.
+fn expand_home(path: &str) -> String {
+    match path.strip_prefix('~') {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => format!("{}{}", home.display(), rest),
+            None => path.to_string(),
+        },
+        None => path.to_string(),
+    }
+}
+
+/// A name predicate: exact, or a `GLOB` when the value has a `*` in it. Both
+/// can use an index on `column`, which `LIKE` cannot.
+fn name_clause(column: &str, value: &str, sql: &mut String, values: &mut Vec<SqlValue>) {
+    if value.contains('*') {
+        sql.push_str(&format!(" AND {} GLOB ?", column));
+        values.push(SqlValue::Text(value.replace('[', "[[]").replace('?', "[?]")));
+    } else {
+        sql.push_str(&format!(" AND {} = ?", column));
+        values.push(SqlValue::Text(value.to_string()));
+    }
+}
+
+/// A path predicate: an absolute (or `~`) path matches itself and everything
+/// under it, anything else matches as a fragment.
+fn path_clause(column: &str, value: &str, sql: &mut String, values: &mut Vec<SqlValue>) {
+    let value = expand_home(value);
+    if value.starts_with('/') {
+        // Everything under `base/` sorts between `base/` and `base0`, the
+        // character after `/`: a range the index can answer.
+        let base = value.trim_end_matches('/');
+        if base.is_empty() {
+            // `dir:/` is every absolute path, `/` included.
+            sql.push_str(&format!(" AND {} LIKE '/%'", column));
+            return;
+        }
+        sql.push_str(&format!(" AND ({0} = ? OR ({0} > ? AND {0} < ?))", column));
+        values.push(SqlValue::Text(base.to_string()));
+        values.push(SqlValue::Text(format!("{}/", base)));
+        values.push(SqlValue::Text(format!("{}0", base)));
+    } else {
+        sql.push_str(&format!(" AND {} LIKE ? ESCAPE '\\'", column));
+        values.push(SqlValue::Text(format!("%{}%", like_pattern(&value))));
+    }
+}
+
+impl Query {
+    /// Nothing typed: browse rather than search.
+    pub fn is_empty(&self) -> bool {
+        *self == Query::default()
+    }
+
+    pub fn has_text(&self) -> bool {
+        !self.terms.is_empty()
+    }
+
+    /// The FTS5 MATCH expression for the free text, if there is any.
+    pub fn match_expr(&self) -> Option<String> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        Some(self.terms.iter().map(|t| fts_phrase(t)).collect::<Vec<_>>().join(" AND "))
+    }
+
+    /// A MATCH expression for anything excluded, to subtract with `NOT IN`.
+    pub fn exclude_expr(&self) -> Option<String> {
+        if self.excluded.is_empty() {
+            return None;
+        }
+        Some(self.excluded.iter().map(|t| fts_phrase(t)).collect::<Vec<_>>().join(" OR "))
+    }
+
+    /// The MATCH expression for a trigram index: all the free text as one
+    /// substring. None when that is shorter than a trigram, which the index
+    /// cannot look up.
+    pub fn substring_expr(&self) -> Option<String> {
+        let text = self.text();
+        let needle = text.trim();
+        (needle.chars().count() >= 3).then(|| fts_phrase(needle))
+    }
+
+    /// The free text as typed, for substring matching and highlighting.
+    pub fn text(&self) -> String {
+        self.terms.join(" ")
+    }
+
+    /// Whether shell commands can match at all.
+    pub fn matches_commands(&self) -> bool {
+        !matches!(self.source, Some(Scope::Agent(_)))
+            && self.model.is_none()
+            && !self.names_summary_labels()
+    }
+
+    /// Whether agent sessions can match at all.
+    pub fn matches_agents(&self) -> bool {
+        self.source != Some(Scope::Shell)
+            && self.branch.is_none()
+            && self.exit.is_none()
+            && self.took.is_none()
+            && !self.names_summary_labels()
+    }
+
+    /// Whether `tag:` or `intent:` asks for summaries alone.
+    fn names_summary_labels(&self) -> bool {
+        self.tag.is_some() || self.intent.is_some()
+    }
+
+    /// Predicates over `commands` aliased as `alias`, each starting with
+    /// ` AND `, plus their bound values in order.
+    pub fn command_clauses(&self, alias: &str) -> (String, Vec<SqlValue>) {
+        let mut sql = String::new();
+        let mut values = Vec::new();
+        if !self.matches_commands() {
+            return (" AND 0".to_string(), values);
+        }
+
+        if let Some(repo) = &self.repo {
+            name_clause(&format!("{}.git_repo", alias), repo, &mut sql, &mut values);
+        }
+        if let Some(branch) = &self.branch {
+            name_clause(&format!("{}.git_branch", alias), branch, &mut sql, &mut values);
+        }
+        match self.exit {
+            Some(Exit::Is(code)) => {
+                sql.push_str(&format!(" AND {}.exit_code = ?", alias));
+                values.push(SqlValue::Integer(code as i64));
+            }
+            Some(Exit::IsNot(code)) => {
+                sql.push_str(&format!(" AND {0}.exit_code IS NOT NULL AND {0}.exit_code != ?", alias));
+                values.push(SqlValue::Integer(code as i64));
+            }
+            None => {}
+        }
+        for path in [&self.dir, &self.project].into_iter().flatten() {
+            path_clause(&format!("{}.cwd", alias), path, &mut sql, &mut values);
+        }
+        if let Some(after) = self.after {
+            sql.push_str(&format!(" AND {}.timestamp >= ?", alias));
+            values.push(SqlValue::Integer(after));
+        }
+        if let Some(before) = self.before {
+            sql.push_str(&format!(" AND {}.timestamp < ?", alias));
+            values.push(SqlValue::Integer(before));
+        }
+        if let Some((cmp, ms)) = self.took {
+            sql.push_str(&format!(" AND {}.duration_ms {} ?", alias, cmp.sql()));
+            values.push(SqlValue::Integer(ms));
+        }
+        if let Some(excluded) = self.exclude_expr() {
+            sql.push_str(&format!(
+                " AND {}.id NOT IN (SELECT rowid FROM commands_fts WHERE commands_fts MATCH ?)",
+                alias
+            ));
+            values.push(SqlValue::Text(excluded));
+        }
+
+        (sql, values)
+    }
+
+    /// Whether session summaries can match. A summary has no repo, directory
+    /// or exit code of its own, so any of those filters rules them out.
+    pub fn matches_summaries(&self) -> bool {
+        matches!(self.source, None | Some(Scope::Shell))
+            && self.model.is_none()
+            && self.repo.is_none()
+            && self.branch.is_none()
+            && self.exit.is_none()
+            && self.dir.is_none()
+            && self.project.is_none()
+            && self.took.is_none()
+    }
+
+    /// Predicates over `summaries` aliased as `alias`, like `command_clauses`.
+    /// A summary is in range when the work it covers overlaps it.
+    pub fn summary_clauses(&self, alias: &str) -> (String, Vec<SqlValue>) {
+        let mut sql = String::new();
+        let mut values = Vec::new();
+        if !self.matches_summaries() {
+            return (" AND 0".to_string(), values);
+        }
+
+        if let Some(after) = self.after {
+            sql.push_str(&format!(" AND COALESCE({0}.ended_at, {0}.created_at) >= ?", alias));
+            values.push(SqlValue::Integer(after));
+        }
+        if let Some(before) = self.before {
+            sql.push_str(&format!(" AND COALESCE({0}.started_at, {0}.created_at) < ?", alias));
+            values.push(SqlValue::Integer(before));
+        }
+        if let Some(tag) = &self.tag {
+            // Tags are a JSON array as the model wrote it; anything else has none.
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid({0}.tags) \
+                 THEN {0}.tags ELSE '[]' END) WHERE lower(value) = lower(?))",
+                alias
+            ));
+            values.push(SqlValue::Text(tag.clone()));
+        }
+        if let Some(intent) = &self.intent {
+            sql.push_str(&format!(" AND lower({}.intent) = lower(?)", alias));
+            values.push(SqlValue::Text(intent.clone()));
+        }
+        if let Some(excluded) = self.exclude_expr() {
+            sql.push_str(&format!(
+                " AND {}.id NOT IN (SELECT rowid FROM summaries_fts WHERE summaries_fts MATCH ?)",
+                alias
+            ));
+            values.push(SqlValue::Text(excluded));
+        }
+
+        (sql, values)
+    }
+
+    /// Predicates over `ai_sessions` aliased as `alias`, like `command_clauses`.
+    /// A session is in range when any of it falls between `after` and
+    /// `before`, and is excluded when any of its text matches.
+    pub fn session_clauses(&self, alias: &str) -> (String, Vec<SqlValue>) {
+        let mut sql = String::new();
+        let mut values = Vec::new();
+        if !self.matches_agents() {
+            return (" AND 0".to_string(), values);
+        }
+
+        if let Some(Scope::Agent(source)) = self.source {
+            sql.push_str(&format!(" AND {}.source = ?", alias));
+            values.push(SqlValue::Text(source.as_str().to_string()));
+        }
+        if let Some(repo) = &self.repo {
+            // Agent sessions have no repo of their own; their project
+            // directory is named after it.
+            sql.push_str(&format!(" AND {}.project LIKE ? ESCAPE '\\'", alias));
+            values.push(SqlValue::Text(format!("%/{}", like_pattern(repo))));
+        }
+        for path in [&self.dir, &self.project].into_iter().flatten() {
+            path_clause(&format!("{}.project", alias), path, &mut sql, &mut values);
+        }
+        if let Some(model) = &self.model {
+            sql.push_str(&format!(" AND {}.model LIKE ? ESCAPE '\\'", alias));
+            values.push(SqlValue::Text(format!("%{}%", like_pattern(model))));
+        }
+        if let Some(after) = self.after {
+            sql.push_str(&format!(" AND {}.last_activity >= ?", alias));
+            values.push(SqlValue::Integer(after));
+        }
+        if let Some(before) = self.before {
+            sql.push_str(&format!(" AND {}.started_at < ?", alias));
+            values.push(SqlValue::Integer(before));
+        }
+        if let Some(excluded) = self.exclude_expr() {
+            sql.push_str(&format!(
+                " AND {}.uid NOT IN (
+                    SELECT x.session_uid FROM ai_chunks_fts
+                    JOIN ai_chunks x ON x.id = ai_chunks_fts.rowid
+                    WHERE ai_chunks_fts MATCH ?)",
+                alias
+            ));
+            values.push(SqlValue::Text(excluded));
+        }
+
+        (sql, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_text_phrases_and_exclusions_are_told_apart() {
+        let q = parse(r#"repo:api branch:main exit:!0 "connection refused" retry -flaky"#).unwrap();
+        assert_eq!(q.repo.as_deref(), Some("api"));
+        assert_eq!(q.branch.as_deref(), Some("main"));
+        assert_eq!(q.exit, Some(Exit::IsNot(0)));
+        assert_eq!(q.terms, vec!["connection refused", "retry"]);
+        assert_eq!(q.excluded, vec!["flaky"]);
+        assert_eq!(
+            q.match_expr().as_deref(),
+            Some(r#""connection refused" AND "retry""#)
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_urls_stay_plain_text() {
+        let q = parse("curl http://localhost:8080 foo:bar").unwrap();
+        assert_eq!(q.terms, vec!["curl", "http://localhost:8080", "foo:bar"]);
+        assert!(q.repo.is_none());
+    }
+
+    #[test]
+    fn durations_and_dates_are_understood() {
+        let q = parse("took:>30s after:2026-09-01 before:yesterday").unwrap();
+        assert_eq!(q.took, Some((Cmp::Gt, 30_000)));
+        assert_eq!(parse("took:2m").unwrap().took, Some((Cmp::Ge, 120_000)));
+        assert_eq!(parse("took:<=500ms").unwrap().took, Some((Cmp::Le, 500)));
+        assert!(q.after.unwrap() < q.before.unwrap());
+        assert!(parse("after:7d").unwrap().after.is_some());
+        let week = parse(r#"since:"last week" until:"last week""#).unwrap();
+        // A week, give or take a daylight-saving hour.
+        let length = week.before.unwrap() - week.after.unwrap();
+        assert!((length - 7 * 86_400_000).abs() <= 3_600_000);
+    }
+
+    #[test]
+    fn a_bad_value_is_an_error_but_a_missing_one_is_not() {
+        assert!(parse("exit:maybe").is_err());
+        assert!(parse("after:someday").is_err());
+        assert!(parse("source:vim").is_err());
+        // Mid-typing in the TUI.
+        assert!(parse("repo:").unwrap().is_empty());
+    }
+
+    #[test]
+    fn quoted_values_can_hold_spaces() {
+        let q = parse(r#"dir:"/Users/me/My Projects" build"#).unwrap();
+        assert_eq!(q.dir.as_deref(), Some("/Users/me/My Projects"));
+        assert_eq!(q.terms, vec!["build"]);
+    }
+
+    #[test]
+    fn shell_only_keys_rule_out_agent_sessions_and_back() {
+        let q = parse("exit:1").unwrap();
+        assert!(q.matches_commands() && !q.matches_agents());
+        let q = parse("model:opus").unwrap();
+        assert!(!q.matches_commands() && q.matches_agents());
+        let q = parse("source:shell").unwrap();
+        assert_eq!(q.session_clauses("s").0, " AND 0");
+        let q = parse("tag:rust").unwrap();
+        assert!(!q.matches_commands() && !q.matches_agents() && q.matches_summaries());
+    }
+
+    #[test]
+    fn an_absolute_dir_matches_the_tree_under_it() {
+        let q = parse("dir:/src/recall").unwrap();
+        let (sql, values) = q.command_clauses("c");
+        assert_eq!(sql, " AND (c.cwd = ? OR (c.cwd > ? AND c.cwd < ?))");
+        assert_eq!(values[0], SqlValue::Text("/src/recall".into()));
+        assert_eq!(values[1], SqlValue::Text("/src/recall/".into()));
+        assert_eq!(values[2], SqlValue::Text("/src/recall0".into()));
+    }
+
+    #[test]
+    fn the_root_dir_matches_every_absolute_path() {
+        for root in ["dir:/", "dir://"] {
+            let q = parse(root).unwrap();
+            let (sql, values) = q.command_clauses("c");
+            assert_eq!(sql, " AND c.cwd LIKE '/%'", "{}", root);
+            assert!(values.is_empty());
+        }
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let (sql, _) = parse("dir:/").unwrap().command_clauses("c");
+        let matched: Vec<String> = conn
+            .prepare(&format!(
+                "SELECT c.cwd FROM (SELECT '/' AS cwd UNION ALL SELECT '/srv/app' \
+                 UNION ALL SELECT 'relative') c WHERE 1{} ORDER BY c.cwd",
+                sql
+            ))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(matched, vec!["/", "/srv/app"]);
+    }
+}
@@ -0,0 +1,239 @@
+//! "Did you mean" for searches that find nothing.
+//!
+//! The vocabulary is what the word indexes already hold: `fts5vocab` tables
+//! over `commands_fts` and `ai_chunks_fts` list every indexed word and how
+//! many rows contain it. A searched word missing from both is swapped for the
+//! closest word that is there, by edit distance, the more common winning a
+//! tie. Nothing is stored beyond the indexes themselves.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use super::query::Query;
+
+/// Vocabulary tables, one per word index.
+const VOCABULARIES: [&str; 2] = ["commands_vocab", "ai_chunks_vocab"];
+
+/// Words shorter than this are left alone: too many real words lie one edit
+/// away from them.
+const MIN_LEN: usize = 4;
+
+/// A search with its misspelled words corrected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// The whole search as it would be typed, filters and all.
+    pub input: String,
+    /// Each word replaced, and what replaced it.
+    pub changes: Vec<(String, String)>,
+}
+
+impl Correction {
+    /// The replacement words, for "did you mean" lines.
+    pub fn words(&self) -> String {
+        self.changes
+            .iter()
+            .map(|(_, to)| to.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Suggest a corrected search, if any of its words are unknown and something
+/// known is close. `input` is what was typed, so the correction keeps its
+/// filters and quoting.
+pub fn suggest(conn: &Connection, input: &str, query: &Query) -> Result<Option<Correction>> {
+    let mut changes: Vec<(String, String)> = Vec::new();
+    for word in query.terms.iter().flat_map(|term| words(term)) {
+        if word.chars().count() < MIN_LEN
+            || changes.iter().any(|(from, _)| *from == word)
+            || is_known(conn, &word)?
+        {
+            continue;
+        }
+        if let Some(closest) = closest(conn, &word)? {
+            changes.push((word, closest));
+        }
+    }
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    let mut corrected = input.to_string();
+    for (from, to) in &changes {
+        corrected = replace_word(&corrected, from, to);
+    }
+    Ok(Some(Correction {
+        input: corrected,
+        changes,
+    }))
+}
+
+/// The words of a term as the index splits them: runs of letters and
+/// digits, lowercased.
+fn words(term: &str) -> Vec<String> {
+    term.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+fn is_known(conn: &Connection, word: &str) -> Result<bool> {
+    for table in VOCABULARIES {
+        let sql = format!("SELECT 1 FROM {} WHERE term = ?", table);
+        if conn.prepare(&sql)?.exists([word])? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The indexed word nearest `word`: fewest edits, then most rows, then
+/// alphabetical so the answer never wavers.
+fn closest(conn: &Connection, word: &str) -> Result<Option<String>> {
+    let length = word.chars().count();
+    let allowed = max_distance(length);
+    let mut best: Option<(usize, i64, String)> = None;
+
+    for table in VOCABULARIES {
+        // Words more than `allowed` longer or shorter need more edits anyway.
+        let sql = format!(
+            "SELECT term, doc FROM {} WHERE length(term) BETWEEN ? AND ?",
+            table
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params![
+            length.saturating_sub(allowed) as i64,
+            (length + allowed) as i64
+        ])?;
+        while let Some(row) = rows.next()? {
+            let term: String = row.get(0)?;
+            let Some(distance) = distance(word, &term, allowed) else {
+                continue;
+            };
+            let docs: i64 = row.get(1)?;
+            let better = match &best {
+                None => true,
+                Some((d, n, t)) => (distance, -docs, &term) < (*d, -*n, t),
+            };
+            if better {
+                best = Some((distance, docs, term));
+            }
+        }
+    }
+
+    Ok(best.map(|(_, _, term)| term))
+}
+
+/// One typo in a short word, two in a longer one.
+fn max_distance(length: usize) -> usize {
+    if length <= 5 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Edits between two words — insertions, deletions, substitutions and
+/// swaps of neighbours — or None if more than `limit`.
+fn distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    // Three rows of the optimal string alignment table.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        let mut row_min = current[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+            row_min = row_min.min(current[j]);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let edits = previous[b.len()];
+    (edits <= limit).then_some(edits)
+}
+
+/// Replace `from` wherever it stands as a whole word, in any case.
+fn replace_word(text: &str, from: &str, to: &str) -> String {
+    let pattern = format!(r"(?i)\b{}\b", regex::escape(from));
+    match regex::Regex::new(&pattern) {
+        Ok(re) => re.replace_all(text, regex::NoExpand(to)).into_owned(),
+        Err(_) => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::parse;
+
+    fn indexed(commands: &[&str]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+        conn.execute("INSERT INTO sessions (id, start_time) VALUES ('s', 0)", [])
+            .unwrap();
+        for (at, text) in commands.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO commands (session_id, command_text, timestamp) VALUES ('s', ?, ?)",
+                rusqlite::params![text, at as i64],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn suggestion(conn: &Connection, input: &str) -> Option<String> {
+        suggest(conn, input, &parse(input).unwrap())
+            .unwrap()
+            .map(|correction| correction.input)
+    }
+
+    #[test]
+    fn edit_distance_counts_swaps_as_one() {
+        assert_eq!(distance("kuberntes", "kubernetes", 2), Some(1));
+        assert_eq!(distance("dokcer", "docker", 2), Some(1));
+        assert_eq!(distance("cargo", "cargo", 2), Some(0));
+        assert_eq!(distance("cargo", "docker", 2), None);
+    }
+
+    #[test]
+    fn a_misspelling_is_corrected_and_the_filters_kept() {
+        let conn = indexed(&["kubectl get pods -n kubernetes-system", "docker ps"]);
+        assert_eq!(
+            suggestion(&conn, "kuberntes repo:api").as_deref(),
+            Some("kubernetes repo:api")
+        );
+        assert_eq!(suggestion(&conn, "Dokcer PS").as_deref(), Some("docker PS"));
+    }
+
+    #[test]
+    fn known_short_and_hopeless_words_are_left_alone() {
+        let conn = indexed(&["kubectl get pods"]);
+        assert_eq!(suggestion(&conn, "kubectl pods"), None);
+        assert_eq!(suggestion(&conn, "pod"), None, "too short to guess at");
+        assert_eq!(suggestion(&conn, "terraform"), None);
+    }
+
+    #[test]
+    fn the_more_common_word_wins_a_tie() {
+        let conn = indexed(&["cargo bulk", "cargo build", "cargo build --release"]);
+        assert_eq!(suggestion(&conn, "buld").as_deref(), Some("build"));
+    }
+}
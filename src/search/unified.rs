@@ -0,0 +1,278 @@
+//! One ranked list across everything recall holds: shell commands, agent
+//! sessions and session summaries.
+//!
+//! Each corpus has its own FTS5 table, and BM25 scores from different tables
+//! are not comparable — a one-line command and a long transcript chunk score
+//! on different scales. Reciprocal-rank fusion sidesteps that: a hit scores
+//! `1 / (K + its place in its own corpus)`, so the best command, the best
+//! session and the best summary stand side by side at the top, and no corpus
+//! can bury the others by sheer size.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use super::query::Query;
+use crate::ai::models::AiSearchResult;
+use crate::ai::search::{self as ai_search, Mode};
+use crate::ai::store::{self, Filter};
+use crate::config::settings::load_config;
+use crate::db::models::{SearchResult, SummarySearchResult};
+use crate::db::queries;
+
+/// Damps the lead of the very first places; 60 is the usual choice.
+const K: f64 = 60.0;
+
+#[derive(Debug, Clone)]
+pub enum Hit {
+    Command(SearchResult),
+    Agent(AiSearchResult),
+    Summary(SummarySearchResult),
+}
+
+impl Hit {
+    /// When the hit happened, for ordering filter-only results.
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            Hit::Command(hit) => hit.command.timestamp,
+            Hit::Agent(hit) => hit.session.last_activity,
+            Hit::Summary(hit) => hit.summary.ended_at.unwrap_or(hit.summary.created_at),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ranked {
+    pub hit: Hit,
+    /// Fused score; higher is better.
+    pub score: f64,
+}
+
+pub struct Found {
+    pub hits: Vec<Ranked>,
+    /// Whether agent sessions needed the substring fallback.
+    pub agent_mode: Mode,
+}
+
+/// Search every corpus the query can match, taking up to `limit` hits from
+/// each, and fuse them best first. With no free text there is nothing to
+/// rank, and the hits come newest first.
+pub fn search(conn: &Connection, query: &Query, limit: usize) -> Result<Found> {
+    let mut commands = if query.matches_commands() {
+        queries::find_commands(conn, query, limit, 0)?
+    } else {
+        Vec::new()
+    };
+    // Like agent sessions, fall back to substrings when no whole word matched.
+    if commands.is_empty() && query.has_text() && query.matches_commands() {
+        commands = queries::find_commands_fuzzy(conn, query, limit, 0)?;
+    }
+
+    let filter = Filter {
+        query: query.clone(),
+        limit,
+        // A config that fails to parse is reported by whatever loads it for
+        // real; search still works with the built-in weights.
+        ranking: load_config().map(|config| config.search.ranking).unwrap_or_default(),
+        ..Default::default()
+    };
+    let (agents, agent_mode) = if !query.matches_agents() {
+        (Vec::new(), Mode::Fts)
+    } else if query.has_text() {
+        ai_search::search(conn, &filter, Mode::Fts)?
+    } else {
+        let sessions = store::list_sessions(conn, &filter)?;
+        let listed = sessions
+            .into_iter()
+            .map(|session| AiSearchResult {
+                session,
+                snippet: String::new(),
+                rank: 0.0,
+            })
+            .collect();
+        (listed, Mode::Fts)
+    };
+
+    let summaries = if query.matches_summaries() {
+        queries::find_summaries(conn, query, limit)?
+    } else {
+        Vec::new()
+    };
+
+    let mut hits = fuse(vec![
+        commands.into_iter().map(Hit::Command).collect(),
+        agents.into_iter().map(Hit::Agent).collect(),
+        summaries.into_iter().map(Hit::Summary).collect(),
+    ]);
+    if !query.has_text() {
+        hits.sort_by_key(|ranked| std::cmp::Reverse(ranked.hit.timestamp()));
+    }
+
+    Ok(Found { hits, agent_mode })
+}
+
+/// The fused score of the hit at `place` (0 for first) in its own list.
+pub fn rrf(place: usize) -> f64 {
+    1.0 / (K + place as f64 + 1.0)
+}
+
+/// Reciprocal-rank fusion of lists that are each already best first. Ties —
+/// the same place in different corpora — go to the more recent hit.
+fn fuse(lists: Vec<Vec<Hit>>) -> Vec<Ranked> {
+    let mut ranked: Vec<Ranked> = lists
+        .into_iter()
+        .flat_map(|list| {
+            list.into_iter().enumerate().map(|(place, hit)| Ranked {
+                hit,
+                score: rrf(place),
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.hit.timestamp().cmp(&a.hit.timestamp()))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::{session_uid, AiSession, Chunk, Source};
+    use crate::db::models::{Command, Session, Summary};
+    use crate::search::query::parse;
+
+    fn seeded() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::initialize_db(&conn).unwrap();
+
+        queries::insert_session(
+            &conn,
+            &Session {
+                id: "s".into(),
+                start_time: 0,
+                end_time: None,
+                terminal_app: None,
+                initial_dir: None,
+            },
+        )
+        .unwrap();
+        for (at, text) in [(10, "kubectl rollout restart api"), (20, "kubectl get pods"), (30, "ls")] {
+            queries::insert_command(
+                &conn,
+                &Command {
+                    id: None,
+                    session_id: "s".into(),
+                    command_text: text.into(),
+                    timestamp: at,
+                    duration_ms: None,
+                    cwd: None,
+                    git_repo: None,
+                    git_branch: None,
+                    exit_code: Some(0),
+                    output: None,
+                },
+            )
+            .unwrap();
+        }
+        queries::insert_summary(
+            &conn,
+            &Summary {
+                id: None,
+                session_id: "s".into(),
+                summary_text: "Restarted the api deployment with kubectl".into(),
+                tags: None,
+                intent: None,
+                created_at: 40,
+                started_at: Some(10),
+                ended_at: Some(30),
+            },
+        )
+        .unwrap();
+
+        let uid = session_uid(Source::Claude, "c1");
+        store::upsert_session(
+            &conn,
+            &AiSession {
+                uid: uid.clone(),
+                source: Source::Claude,
+                session_id: "c1".into(),
+                project: "/p".into(),
+                title: None,
+                started_at: 50,
+                last_activity: 50,
+                model: None,
+                message_count: 1,
+                file_path: "/tmp/c1.jsonl".into(),
+                file_mtime: 0,
+                file_size: 0,
+                custom_name: None,
+            },
+            0,
+        )
+        .unwrap();
+        store::insert_chunk(
+            &conn,
+            &Chunk {
+                chunk_id: "c1-0".into(),
+                session_uid: uid,
+                source: Source::Claude,
+                project: "/p".into(),
+                title: None,
+                timestamp: 50,
+                text: "why does kubectl rollout hang on the api deployment".into(),
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    fn kinds(found: &Found) -> Vec<&'static str> {
+        found
+            .hits
+            .iter()
+            .map(|ranked| match ranked.hit {
+                Hit::Command(_) => "command",
+                Hit::Agent(_) => "agent",
+                Hit::Summary(_) => "summary",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_best_of_each_corpus_leads_the_list() {
+        let conn = seeded();
+        let found = search(&conn, &parse("kubectl").unwrap(), 10).unwrap();
+        // Three corpora share first place, newest first; the second command follows.
+        assert_eq!(kinds(&found), vec!["agent", "summary", "command", "command"]);
+        assert!(found.hits[0].score > found.hits[3].score);
+    }
+
+    #[test]
+    fn filters_decide_which_corpora_take_part() {
+        let conn = seeded();
+        let shell = search(&conn, &parse("kubectl source:shell").unwrap(), 10).unwrap();
+        assert_eq!(kinds(&shell), vec!["summary", "command", "command"]);
+        let exit = search(&conn, &parse("kubectl exit:0").unwrap(), 10).unwrap();
+        assert_eq!(kinds(&exit), vec!["command", "command"]);
+        let agents = search(&conn, &parse("rollout source:claude").unwrap(), 10).unwrap();
+        assert_eq!(kinds(&agents), vec!["agent"]);
+    }
+
+    #[test]
+    fn a_fragment_falls_back_to_substring_matches() {
+        let conn = seeded();
+        let found = search(&conn, &parse("ubect").unwrap(), 10).unwrap();
+        assert_eq!(kinds(&found), vec!["agent", "command", "command"]);
+        assert_eq!(found.agent_mode, Mode::Fuzzy);
+    }
+
+    #[test]
+    fn without_text_hits_come_newest_first() {
+        let conn = seeded();
+        let found = search(&conn, &parse("source:shell").unwrap(), 10).unwrap();
+        let times: Vec<i64> = found.hits.iter().map(|r| r.hit.timestamp()).collect();
+        assert_eq!(times, vec![30, 30, 20, 10]);
+    }
+}
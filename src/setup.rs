@@ -1,13 +1,14 @@
 //! `recall setup` — everything a new user needs, in one command.
 //!
 //! Setup indexes agent sessions and then reports what else is worth doing. It
-//! never edits `~/.zshrc`: that file is the user's, often generated or version
-//! controlled, and a tool writing to it behind their back is a surprise nobody
-//! asked for. Anything that needs changing there is printed to copy instead.
+//! never edits `~/.zshrc` or `~/.bashrc`: those files are the user's, often
+//! generated or version controlled, and a tool writing to them behind their
+//! back is a surprise nobody asked for. Anything that needs changing there is
+//! printed to copy instead.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::Colorize;
-use std::io::{BufRead, IsTerminal, Write};
+use std::io::BufRead;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -15,9 +16,8 @@ use crate::ai::indexer;
 use crate::ai::models::AiSession;
 use crate::ai::store::{self, Filter};
 use crate::llm::cli_backend;
+use crate::shell::Shell;
 
-/// Marker recall looks for when deciding whether the hook is already installed.
-const HOOK_MARKER: &str = "recall init zsh";
 /// An alias pointing at a specific binary shadows whatever is on PATH.
 const ALIAS_MARKER: &str = "alias recall=";
 const RULE: usize = 60;
@@ -30,24 +30,38 @@ pub fn run() -> Result<()> {
     println!("  {}", "─".repeat(RULE).dimmed());
 
     let latest = index_agent_sessions(&conn)?;
+    if let Some(report) = crate::gc::run_if_due(&conn)? {
+        if !report.reclaimed.is_empty() {
+            println!(
+                "  {}   {}",
+                " ".dimmed(),
+                format!(
+                    "[retention] limits applied: {} reclaimed",
+                    crate::gc::format_bytes((report.bytes_before - report.bytes_after).max(0))
+                )
+                .dimmed()
+            );
+        }
+    }
     println!();
-    let hook = report_shell_hook();
+    let shell = Shell::from_env();
+    let hook = report_shell_hook(shell);
     println!();
     let backend = report_ask_backend();
 
     println!("  {}", "─".repeat(RULE).dimmed());
-    print_next_steps(latest.as_ref(), hook, backend.is_some());
+    print_next_steps(latest.as_ref(), hook, shell, backend.is_some());
     Ok(())
 }
 
-/// Index Claude Code and Codex transcripts, and report the most recent session
+/// Index agent transcripts, and report the most recent session
 /// back to the user — proof the index is real and personal.
 fn index_agent_sessions(conn: &rusqlite::Connection) -> Result<Option<AiSession>> {
     println!(
         "  {} {} {}",
         "┌".dimmed(),
         section("Agent sessions"),
-        "reading transcripts Claude Code & Codex already keep on disk".dimmed()
+        "reading transcripts your agent tools already keep on disk".dimmed()
     );
 
     let started = Instant::now();
@@ -59,7 +73,7 @@ fn index_agent_sessions(conn: &rusqlite::Connection) -> Result<Option<AiSession>
         println!(
             "  {} {}",
             "└".dimmed(),
-            "no Claude Code or Codex transcripts found — this lights up as soon as you use either"
+            "no agent transcripts found — this lights up as soon as you use an agent"
                 .dimmed()
         );
         return Ok(None);
@@ -95,6 +109,17 @@ fn index_agent_sessions(conn: &rusqlite::Connection) -> Result<Option<AiSession>
             format!("{} transcripts could not be parsed", report.failed.len()).yellow()
         );
     }
+    if report.redacted > 0 {
+        println!(
+            "  {}   {}",
+            " ".dimmed(),
+            format!(
+                "{} secrets found in transcripts were redacted from the index",
+                report.redacted
+            )
+            .dimmed()
+        );
+    }
 
     Ok(store::list_sessions(conn, &Filter::with_limit(1))?
         .into_iter()
@@ -108,10 +133,16 @@ pub enum HookStatus {
     /// Installed, but running some other recall binary.
     Stale,
     Missing,
-    NotZsh,
+    /// The login shell is one recall has no hook for.
+    Unsupported,
 }
 
-/// A line in `~/.zshrc` that runs a specific recall binary.
+/// Marker recall looks for when deciding whether the hook is already installed.
+fn hook_marker(shell: Shell) -> String {
+    format!("recall init {}", shell.as_str())
+}
+
+/// A line in the shell's startup file that runs a specific recall binary.
 #[derive(Debug, Clone)]
 struct WiredLine {
     number: usize,
@@ -121,8 +152,9 @@ struct WiredLine {
 }
 
 /// Every uncommented line that wires up a recall binary, with the path it runs.
-fn wired_lines(zshrc: &Path) -> Vec<WiredLine> {
-    let contents = match std::fs::read_to_string(zshrc) {
+fn wired_lines(rc: &Path, shell: Shell) -> Vec<WiredLine> {
+    let marker = hook_marker(shell);
+    let contents = match std::fs::read_to_string(rc) {
         Ok(contents) => contents,
         Err(_) => return Vec::new(),
     };
@@ -132,8 +164,8 @@ fn wired_lines(zshrc: &Path) -> Vec<WiredLine> {
         .enumerate()
         .filter(|(_, line)| !line.trim_start().starts_with('#'))
         .filter_map(|(index, line)| {
-            let (binary, is_alias) = if line.contains(HOOK_MARKER) {
-                (hook_binary(line)?, false)
+            let (binary, is_alias) = if line.contains(&marker) {
+                (hook_binary(line, shell)?, false)
             } else if line.contains(ALIAS_MARKER) {
                 (alias_binary(line)?, true)
             } else {
@@ -152,10 +184,10 @@ fn wired_lines(zshrc: &Path) -> Vec<WiredLine> {
 
 /// `eval "$(/path/to/recall init zsh)"` -> `/path/to/recall`
 ///
-/// Split on the arguments only: `HOOK_MARKER` starts with the binary name, so
+/// Split on the arguments only: the hook marker starts with the binary name, so
 /// splitting on it would swallow the last path segment.
-fn hook_binary(line: &str) -> Option<PathBuf> {
-    let before = line.split(" init zsh").next()?;
+fn hook_binary(line: &str, shell: Shell) -> Option<PathBuf> {
+    let before = line.split(&format!(" init {}", shell.as_str())).next()?;
     let start = before.rfind("$(").map(|i| i + 2).unwrap_or(0);
     Some(expand_home(before[start..].trim().trim_matches('"').trim_matches('\'')))
 }
@@ -195,14 +227,15 @@ fn on_path(binary: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// The lines to add or change in `~/.zshrc`, and how to run recall without
-/// touching that file at all.
-fn print_manual_steps(current: &Path, stale: &[WiredLine]) {
-    let line = format!("eval \"$({} init zsh)\"", current.display());
+/// The lines to add or change in the shell's startup file, and how to run
+/// recall without touching that file at all.
+fn print_manual_steps(current: &Path, stale: &[WiredLine], shell: Shell) {
+    let line = hook_line(current, shell);
+    let rc = shell.rc_display();
     println!();
     println!(
         "    {}  {}",
-        "To record shell commands, edit ~/.zshrc yourself:".bold(),
+        format!("To record shell commands, edit {} yourself:", rc).bold(),
         "(recall never writes to it)".dimmed()
     );
 
@@ -233,19 +266,17 @@ fn print_manual_steps(current: &Path, stale: &[WiredLine]) {
     step += 1;
 
     if !on_path(current) {
-        println!("      {}. Put recall on your PATH, also in ~/.zshrc:", step);
-        println!(
-            "           {}",
-            format!(
-                "export PATH=\"{}:$PATH\"",
-                current.parent().unwrap_or(Path::new("")).display()
-            )
-            .cyan()
-        );
+        println!("      {}. Put recall on your PATH, also in {}:", step, rc);
+        let dir = current.parent().unwrap_or(Path::new("")).display();
+        let path_line = match shell {
+            Shell::Fish => format!("fish_add_path {}", dir),
+            _ => format!("export PATH=\"{}:$PATH\"", dir),
+        };
+        println!("           {}", path_line.cyan());
         step += 1;
     }
 
-    println!("      {}. Reload:  {}", step, "source ~/.zshrc".cyan());
+    println!("      {}. Reload:  {}", step, format!("source {}", rc).cyan());
     println!();
     println!(
         "    {}",
@@ -259,9 +290,9 @@ fn print_manual_steps(current: &Path, stale: &[WiredLine]) {
     println!();
 }
 
-/// Report how `~/.zshrc` is wired up, and print anything worth changing.
-/// Nothing here writes to the file.
-fn report_shell_hook() -> HookStatus {
+/// Report how the login shell's startup file is wired up, and print anything
+/// worth changing. Nothing here writes to the file.
+fn report_shell_hook(shell: Option<Shell>) -> HookStatus {
     println!(
         "  {} {} {}",
         "┌".dimmed(),
@@ -269,20 +300,39 @@ fn report_shell_hook() -> HookStatus {
         "captures every command you run from now on".dimmed()
     );
 
-    let shell = std::env::var("SHELL").unwrap_or_default();
-    if !shell.ends_with("zsh") {
-        println!(
-            "  {} {} {}",
-            "└".dimmed(),
-            "skipped".yellow(),
-            format!("recall records zsh only; your shell is {}", shell).dimmed()
-        );
-        return HookStatus::NotZsh;
+    // A hook in another shell's startup file still records whenever that
+    // shell is opened, so it is worth a mention either way.
+    for other in Shell::ALL.into_iter().filter(|s| Some(*s) != shell) {
+        if hook_present(&other.rc_file(), other) {
+            println!(
+                "  {}   {}",
+                "│".dimmed(),
+                format!("{} hook found in {}", other.as_str(), other.rc_display()).dimmed()
+            );
+        }
     }
 
+    let shell = match shell {
+        Some(shell) => shell,
+        None => {
+            let supported: Vec<&str> = Shell::ALL.iter().map(|s| s.as_str()).collect();
+            println!(
+                "  {} {} {}",
+                "└".dimmed(),
+                "skipped".yellow(),
+                format!(
+                    "recall records {}; your shell is {}",
+                    supported.join(", "),
+                    std::env::var("SHELL").unwrap_or_default()
+                )
+                .dimmed()
+            );
+            return HookStatus::Unsupported;
+        }
+    };
+
     let current = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("recall"));
-    let zshrc = zshrc_path();
-    let wired = wired_lines(&zshrc);
+    let wired = wired_lines(&shell.rc_file(), shell);
     let stale: Vec<WiredLine> = wired
         .iter()
         .filter(|entry| !same_binary(&entry.binary, &current))
@@ -291,7 +341,7 @@ fn report_shell_hook() -> HookStatus {
 
     if wired.is_empty() {
         println!("  {} {}", "└".dimmed(), "not set up yet".yellow());
-        print_manual_steps(&current, &[]);
+        print_manual_steps(&current, &[], shell);
         return HookStatus::Missing;
     }
 
@@ -300,7 +350,7 @@ fn report_shell_hook() -> HookStatus {
             "  {} {} {}",
             "└".dimmed(),
             "✓".green(),
-            "installed in ~/.zshrc and pointing here".dimmed()
+            format!("installed in {} and pointing here", shell.rc_display()).dimmed()
         );
         return HookStatus::Current;
     }
@@ -323,7 +373,7 @@ fn report_shell_hook() -> HookStatus {
         "└".dimmed(),
         format!("they run a different binary than this one ({})", current.display()).dimmed()
     );
-    print_manual_steps(&current, &stale);
+    print_manual_steps(&current, &stale, shell);
     HookStatus::Stale
 }
 
@@ -366,7 +416,12 @@ fn report_ask_backend() -> Option<String> {
     None
 }
 
-fn print_next_steps(latest: Option<&AiSession>, hook: HookStatus, has_backend: bool) {
+fn print_next_steps(
+    latest: Option<&AiSession>,
+    hook: HookStatus,
+    shell: Option<Shell>,
+    has_backend: bool,
+) {
     println!("  {}", "Try it now:".bold());
     println!();
 
@@ -404,13 +459,16 @@ fn print_next_steps(latest: Option<&AiSession>, hook: HookStatus, has_backend: b
         );
     }
 
-    if hook == HookStatus::Current {
+    if let (HookStatus::Current, Some(shell)) = (hook, shell) {
         println!();
         println!(
             "  {} {}",
             "●".dimmed(),
-            "Open a new terminal (or run `source ~/.zshrc`) if commands aren't being recorded."
-                .dimmed()
+            format!(
+                "Open a new terminal (or run `source {}`) if commands aren't being recorded.",
+                shell.rc_display()
+            )
+            .dimmed()
         );
     }
 
@@ -436,28 +494,25 @@ fn section(label: &str) -> colored::ColoredString {
     format!("{:<16}", label).white().bold()
 }
 
-fn zshrc_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".zshrc")
-}
-
-/// The eval line, with the absolute path of the running binary baked in, so it
-/// keeps working whether recall was installed globally or built in a repo.
-fn hook_line() -> Result<String> {
-    let exe = std::env::current_exe().context("Could not determine the recall binary path")?;
-    Ok(format!("eval \"$({} init zsh)\"", exe.display()))
+/// The hook line, with the absolute path of the binary baked in, so it keeps
+/// working whether recall was installed globally or built in a repo.
+fn hook_line(binary: &Path, shell: Shell) -> String {
+    match shell {
+        Shell::Fish => format!("{} init fish | source", binary.display()),
+        _ => format!("eval \"$({} init {})\"", binary.display(), shell.as_str()),
+    }
 }
 
-fn hook_present(zshrc: &Path) -> bool {
-    let file = match std::fs::File::open(zshrc) {
+fn hook_present(rc: &Path, shell: Shell) -> bool {
+    let marker = hook_marker(shell);
+    let file = match std::fs::File::open(rc) {
         Ok(file) => file,
         Err(_) => return false,
     };
     std::io::BufReader::new(file)
         .lines()
         .map_while(Result::ok)
-        .any(|line| line.contains(HOOK_MARKER) && !line.trim_start().starts_with('#'))
+        .any(|line| line.contains(&marker) && !line.trim_start().starts_with('#'))
 }
 
 /// Rough relative time, e.g. "2h ago".
@@ -498,26 +553,38 @@ mod tests {
     #[test]
     fn hook_is_detected_when_present() {
         let path = temp_file("present", "export FOO=1\neval \"$(/bin/recall init zsh)\"\n");
-        assert!(hook_present(&path));
+        assert!(hook_present(&path, Shell::Zsh));
         std::fs::remove_file(&path).ok();
     }
 
     #[test]
     fn a_commented_out_hook_does_not_count() {
         let path = temp_file("commented", "# eval \"$(/bin/recall init zsh)\"\n");
-        assert!(!hook_present(&path));
+        assert!(!hook_present(&path, Shell::Zsh));
         std::fs::remove_file(&path).ok();
     }
 
     #[test]
     fn a_missing_zshrc_reports_no_hook() {
-        assert!(!hook_present(Path::new("/nonexistent/.zshrc")));
+        assert!(!hook_present(Path::new("/nonexistent/.zshrc"), Shell::Zsh));
+    }
+
+    #[test]
+    fn a_bash_hook_is_detected_in_bashrc() {
+        let path = temp_file("bashrc", "eval \"$(/bin/recall init bash)\"\n");
+        assert!(hook_present(&path, Shell::Bash));
+        assert!(!hook_present(&path, Shell::Zsh), "a bash hook is not a zsh hook");
+        assert_eq!(
+            wired_lines(&path, Shell::Bash)[0].binary,
+            PathBuf::from("/bin/recall")
+        );
+        std::fs::remove_file(&path).ok();
     }
 
     #[test]
     fn hook_binary_is_read_out_of_an_eval_line() {
         assert_eq!(
-            hook_binary(r#"eval "$(/usr/local/bin/recall init zsh)""#),
+            hook_binary(r#"eval "$(/usr/local/bin/recall init zsh)""#, Shell::Zsh),
             Some(PathBuf::from("/usr/local/bin/recall"))
         );
     }
@@ -526,7 +593,10 @@ mod tests {
     fn hook_binary_expands_a_home_relative_path() {
         let home = dirs::home_dir().unwrap();
         assert_eq!(
-            hook_binary(r#"eval "$(~/repos/recall/target/release/recall init zsh)""#),
+            hook_binary(
+                r#"eval "$(~/repos/recall/target/release/recall init zsh)""#,
+                Shell::Zsh
+            ),
             Some(home.join("repos/recall/target/release/recall"))
         );
     }
@@ -552,7 +622,7 @@ mod tests {
              # eval \"$(/commented/recall init zsh)\"\n\
              eval \"$(/old/recall init zsh)\"\n",
         );
-        let found = wired_lines(&path);
+        let found = wired_lines(&path, Shell::Zsh);
         assert_eq!(found.len(), 2, "the commented line does not count");
         assert_eq!(found[0].number, 2);
         assert!(found[0].is_alias);
@@ -585,9 +655,25 @@ mod tests {
 
     #[test]
     fn hook_line_embeds_an_absolute_path() {
-        let line = hook_line().unwrap();
+        let exe = std::env::current_exe().unwrap();
+        let line = hook_line(&exe, Shell::Zsh);
         assert!(line.starts_with("eval \"$(/"));
         assert!(line.ends_with(" init zsh)\""));
+        assert!(hook_line(&exe, Shell::Bash).ends_with(" init bash)\""));
+    }
+
+    #[test]
+    fn a_fish_hook_is_piped_to_source() {
+        let line = hook_line(Path::new("/opt/recall"), Shell::Fish);
+        assert_eq!(line, "/opt/recall init fish | source");
+
+        let path = temp_file("config.fish", &format!("{}\n", line));
+        assert!(hook_present(&path, Shell::Fish));
+        assert_eq!(
+            wired_lines(&path, Shell::Fish)[0].binary,
+            PathBuf::from("/opt/recall")
+        );
+        std::fs::remove_file(&path).ok();
     }
 
     #[test]
@@ -0,0 +1,148 @@
+/// Output the bash hook script to stdout.
+/// User activates with: eval "$(recall init bash)"
+///
+/// bash has no preexec hook of its own. When bash-preexec is already loaded
+/// the hook registers with it; otherwise a DEBUG trap stands in for preexec
+/// and PROMPT_COMMAND for precmd, the same pairing bash-preexec uses.
+pub fn print_hook() {
+    let bin = std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "recall".to_string());
+
+    print!("{}", hook_script(&bin));
+}
+
+fn hook_script(bin: &str) -> String {
+    format!(
+        r#"__recall_now_ms() {{
+    if [[ -n "${{EPOCHREALTIME:-}}" ]]; then
+        local now=${{EPOCHREALTIME/[.,]/}}
+        echo $(( now / 1000 ))
+    else
+        python3 -c 'import time;print(int(time.time()*1000))'
+    fi
+}}
+
+__recall_preexec() {{
+    export __RECALL_CMD="$1"
+    export __RECALL_START=$(__recall_now_ms)
+    export __RECALL_OUTPUT_FILE=$(mktemp /tmp/recall_output.XXXXXX)
+    # Record current position in the script typescript file
+    if [[ -n "$__RECALL_TYPESCRIPT" && -f "$__RECALL_TYPESCRIPT" ]]; then
+        export __RECALL_TS_POS=$(command wc -c < "$__RECALL_TYPESCRIPT" | tr -d ' ')
+    fi
+}}
+
+__recall_precmd() {{
+    local exit_code=$?
+    if [ -n "$__RECALL_CMD" ]; then
+        # Extract this command's output from the typescript file
+        if [[ -n "$__RECALL_TYPESCRIPT" && -n "$__RECALL_TS_POS" && -f "$__RECALL_TYPESCRIPT" ]]; then
+            tail -c +$((__RECALL_TS_POS + 1)) "$__RECALL_TYPESCRIPT" > "$__RECALL_OUTPUT_FILE" 2>/dev/null
+        fi
+        # A subshell keeps the background job out of the shell's job table,
+        # so no "[1] 12345" notice is printed after every command.
+        ( "{bin}" log \
+            --command "$__RECALL_CMD" \
+            --exit-code $exit_code \
+            --start "$__RECALL_START" \
+            --cwd "$PWD" \
+            --session "$RECALL_SESSION_ID" \
+            --terminal "${{TERM_PROGRAM:-${{TERMINAL_EMULATOR:-${{LC_TERMINAL:-Terminal}}}}}}" \
+            --output-file "$__RECALL_OUTPUT_FILE" & )
+        unset __RECALL_CMD
+    fi
+}}
+
+# DEBUG fires before every simple command, PROMPT_COMMAND's own included, so
+# only the first one after a prompt is something the user typed.
+__recall_debug_trap() {{
+    [[ -n "$__RECALL_ARMED" ]] || return 0
+    [[ -n "${{COMP_LINE:-}}" ]] && return 0
+    unset __RECALL_ARMED
+    # An empty line runs nothing, so the next thing through is the prompt.
+    [[ "$BASH_COMMAND" == __recall_precmd* ]] && return 0
+
+    local entry re='^ *([0-9]+)[* ] *(.*)$'
+    entry=$(HISTTIMEFORMAT= builtin history 1)
+    if [[ $entry =~ $re && ${{BASH_REMATCH[1]}} != "$__RECALL_HISTNO" ]]; then
+        __recall_preexec "${{BASH_REMATCH[2]}}"
+    else
+        # Kept out of history (ignorespace, ignoredups): the command about to
+        # run is the best record there is.
+        __recall_preexec "$BASH_COMMAND"
+    fi
+}}
+
+# Not backgrounded: the terminal may be going away, taking a child with it.
+__recall_exit() {{
+    "{bin}" log --end-session --session "$RECALL_SESSION_ID" 2>/dev/null
+}}
+
+__recall_arm() {{
+    local entry
+    entry=$(HISTTIMEFORMAT= builtin history 1)
+    [[ $entry =~ ^\ *([0-9]+) ]] && __RECALL_HISTNO=${{BASH_REMATCH[1]}}
+    __RECALL_ARMED=1
+}}
+
+if [[ $- == *i* ]]; then
+    export RECALL_SESSION_ID=$("{bin}" session-id)
+
+    # Run ahead of any EXIT trap already set, rather than replacing it.
+    __recall_prev_exit=$(trap -p EXIT)
+    __recall_prev_exit=${{__recall_prev_exit#trap -- }}
+    eval "__recall_prev_exit=${{__recall_prev_exit% EXIT}}"
+    trap "__recall_exit${{__recall_prev_exit:+; $__recall_prev_exit}}" EXIT
+
+    if [[ -n "${{bash_preexec_imported:-}}" ]]; then
+        preexec_functions+=(__recall_preexec)
+        precmd_functions+=(__recall_precmd)
+    else
+        trap '__recall_debug_trap' DEBUG
+        # precmd goes first, while $? still holds the command's exit code, and
+        # arming goes last, after everything else PROMPT_COMMAND runs.
+        if (( BASH_VERSINFO[0] * 100 + BASH_VERSINFO[1] >= 501 )); then
+            PROMPT_COMMAND=(__recall_precmd "${{PROMPT_COMMAND[@]}}" __recall_arm)
+        else
+            PROMPT_COMMAND="__recall_precmd${{PROMPT_COMMAND:+; $PROMPT_COMMAND}}; __recall_arm"
+        fi
+    fi
+
+    # Start a script session to capture output through a PTY (preserves colors)
+    if [[ -z "$__RECALL_TYPESCRIPT" ]]; then
+        export __RECALL_TYPESCRIPT=$(mktemp /tmp/recall_typescript.XXXXXX)
+        SHELL=$(command -v bash) exec script -q "$__RECALL_TYPESCRIPT"
+    fi
+fi
+"#,
+        bin = bin
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_hook_is_valid_bash() {
+        let script = hook_script("/usr/local/bin/recall");
+        let status = std::process::Command::new("bash")
+            .arg("-n")
+            .arg("-c")
+            .arg(&script)
+            .status();
+        // Machines without bash have nothing to check against.
+        if let Ok(status) = status {
+            assert!(status.success(), "bash -n rejected the hook");
+        }
+    }
+
+    #[test]
+    fn the_hook_logs_through_the_embedded_binary() {
+        let script = hook_script("/opt/recall");
+        assert!(script.contains(r#"( "/opt/recall" log \"#));
+        assert!(script.contains(r#"RECALL_SESSION_ID=$("/opt/recall" session-id)"#));
+        assert!(script.contains(r#""/opt/recall" log --end-session"#));
+    }
+}
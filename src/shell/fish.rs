@@ -0,0 +1,104 @@
+/// Output the fish hook script to stdout.
+/// User activates with: recall init fish | source
+///
+/// fish hands the command line to `fish_preexec` and the exit status and
+/// `$CMD_DURATION` to `fish_postexec`, so unlike zsh and bash the hook does not
+/// time the command itself: recall is told the duration and works the start
+/// back from it.
+pub fn print_hook() {
+    let bin = std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "recall".to_string());
+
+    print!("{}", hook_script(&bin));
+}
+
+fn hook_script(bin: &str) -> String {
+    format!(
+        r#"if status is-interactive
+    set -gx RECALL_SESSION_ID ("{bin}" session-id)
+
+    function __recall_preexec --on-event fish_preexec
+        set -g __recall_output_file (mktemp /tmp/recall_output.XXXXXX)
+        set -e __recall_ts_pos
+        # Record current position in the script typescript file
+        if set -q __RECALL_TYPESCRIPT; and test -f "$__RECALL_TYPESCRIPT"
+            set -g __recall_ts_pos (command wc -c < "$__RECALL_TYPESCRIPT" | string trim)
+        end
+    end
+
+    function __recall_postexec --on-event fish_postexec
+        set -l exit_code $status
+        set -l duration $CMD_DURATION
+        # Both events fire for an empty line too; there is nothing to record.
+        if not string length -q -- (string trim -- "$argv[1]")
+            set -q __recall_output_file; and command rm -f "$__recall_output_file"
+            return
+        end
+        # Extract this command's output from the typescript file
+        if set -q __recall_ts_pos; and test -f "$__RECALL_TYPESCRIPT"
+            tail -c +(math $__recall_ts_pos + 1) "$__RECALL_TYPESCRIPT" > "$__recall_output_file" 2>/dev/null
+        end
+        set -l terminal Terminal
+        for candidate in $TERM_PROGRAM $TERMINAL_EMULATOR $LC_TERMINAL
+            if test -n "$candidate"
+                set terminal $candidate
+                break
+            end
+        end
+        "{bin}" log \
+            --command "$argv[1]" \
+            --exit-code $exit_code \
+            --duration $duration \
+            --cwd "$PWD" \
+            --session "$RECALL_SESSION_ID" \
+            --terminal "$terminal" \
+            --output-file "$__recall_output_file" &
+        # Out of the job table, so fish prints no "Job 1 has ended" notice.
+        disown
+        set -e __recall_output_file
+    end
+
+    # Not backgrounded: the terminal may be going away, taking a child with it.
+    function __recall_exit --on-event fish_exit
+        "{bin}" log --end-session --session "$RECALL_SESSION_ID" 2>/dev/null
+    end
+
+    # Start a script session to capture output through a PTY (preserves colors)
+    if not set -q __RECALL_TYPESCRIPT
+        set -gx __RECALL_TYPESCRIPT (mktemp /tmp/recall_typescript.XXXXXX)
+        exec env SHELL=(command -s fish) script -q "$__RECALL_TYPESCRIPT"
+    end
+end
+"#,
+        bin = bin
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_hook_is_valid_fish() {
+        let script = hook_script("/usr/local/bin/recall");
+        let status = std::process::Command::new("fish")
+            .arg("--no-execute")
+            .arg("-c")
+            .arg(&script)
+            .status();
+        // Machines without fish have nothing to check against.
+        if let Ok(status) = status {
+            assert!(status.success(), "fish --no-execute rejected the hook");
+        }
+    }
+
+    #[test]
+    fn the_hook_passes_fish_timing_through() {
+        let script = hook_script("/opt/recall");
+        assert!(script.contains(r#""/opt/recall" log \"#));
+        assert!(script.contains("--duration $duration"));
+        assert!(script.contains("--on-event fish_postexec"));
+        assert!(script.contains("--on-event fish_exit"));
+    }
+}
@@ -1 +1,81 @@
+pub mod bash;
+pub mod fish;
 pub mod zsh;
+
+use std::path::PathBuf;
+
+/// A shell recall can record through a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 3] = [Shell::Zsh, Shell::Bash, Shell::Fish];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Shell::Zsh => "zsh",
+            Shell::Bash => "bash",
+            Shell::Fish => "fish",
+        }
+    }
+
+    /// Accepts a bare name or a path to the binary, as `$SHELL` holds.
+    pub fn parse(name: &str) -> Option<Shell> {
+        match name.rsplit('/').next().unwrap_or(name) {
+            "zsh" => Some(Shell::Zsh),
+            "bash" => Some(Shell::Bash),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+
+    /// The user's login shell, when it is one recall has a hook for.
+    pub fn from_env() -> Option<Shell> {
+        std::env::var("SHELL").ok().as_deref().and_then(Shell::parse)
+    }
+
+    /// The startup file the hook line belongs in, spelled the way a user would type it.
+    pub fn rc_display(&self) -> &'static str {
+        match self {
+            Shell::Zsh => "~/.zshrc",
+            Shell::Bash => "~/.bashrc",
+            Shell::Fish => "~/.config/fish/config.fish",
+        }
+    }
+
+    pub fn rc_file(&self) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home.join(self.rc_display().trim_start_matches("~/"))
+    }
+
+    pub fn print_hook(&self) {
+        match self {
+            Shell::Zsh => zsh::print_hook(),
+            Shell::Bash => bash::print_hook(),
+            Shell::Fish => fish::print_hook(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_names_and_paths() {
+        assert_eq!(Shell::parse("zsh"), Some(Shell::Zsh));
+        assert_eq!(Shell::parse("/bin/bash"), Some(Shell::Bash));
+        assert_eq!(Shell::parse("/usr/local/bin/nu"), None);
+    }
+
+    #[test]
+    fn rc_files_live_in_home() {
+        assert!(Shell::Bash.rc_file().ends_with(".bashrc"));
+        assert!(Shell::Zsh.rc_file().ends_with(".zshrc"));
+        assert!(Shell::Fish.rc_file().ends_with(".config/fish/config.fish"));
+    }
+}
@@ -39,10 +39,16 @@ __recall_precmd() {{
     fi
 }}
 
+# Not backgrounded: the terminal may be going away, taking a child with it.
+__recall_zshexit() {{
+    "{bin}" log --end-session --session "$RECALL_SESSION_ID" 2>/dev/null
+}}
+
 export RECALL_SESSION_ID=$("{bin}" session-id)
 autoload -Uz add-zsh-hook
 add-zsh-hook preexec __recall_preexec
 add-zsh-hook precmd __recall_precmd
+add-zsh-hook zshexit __recall_zshexit
 
 # Start a script session to capture output through a PTY (preserves colors)
 if [[ -z "$__RECALL_TYPESCRIPT" ]]; then
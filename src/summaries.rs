@@ -0,0 +1,195 @@
+//! `recall summaries` — the session summaries `recall summarize` wrote,
+//! newest first, searchable and filterable by the tags and intent the model
+//! gave each one.
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::db::models::Summary;
+use crate::db::queries;
+use crate::search::{dates, query};
+
+const RULE: usize = 60;
+const WIDTH: usize = 86;
+
+/// What to list: free text and filters, as typed and as flags.
+pub struct Options {
+    pub query: Option<String>,
+    pub tag: Option<String>,
+    pub intent: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: usize,
+}
+
+pub fn run(opts: &Options) -> Result<()> {
+    let input = opts.query.clone().unwrap_or_default();
+    let mut query = query::parse(&input)?;
+    if opts.tag.is_some() {
+        query.tag = opts.tag.clone();
+    }
+    if opts.intent.is_some() {
+        query.intent = opts.intent.clone();
+    }
+    if let Some(since) = &opts.since {
+        query.after = Some(dates::parse(since)?.start);
+    }
+    if let Some(until) = &opts.until {
+        query.before = Some(dates::parse(until)?.end);
+    }
+
+    let conn = crate::db::schema::open_db()?;
+    let found = queries::find_summaries(&conn, &query, opts.limit.max(1))?;
+
+    if found.is_empty() {
+        let note = if query.is_empty() {
+            "No summaries yet. `recall summarize` writes them for finished work sessions.".to_string()
+        } else {
+            "No summaries match.".to_string()
+        };
+        println!("\n  {} {}\n", "●".dimmed(), note.dimmed());
+        return Ok(());
+    }
+
+    let title = if query.is_empty() {
+        "Summaries".to_string()
+    } else {
+        format!("Summaries: \"{}\"", describe(opts))
+    };
+    println!();
+    println!(
+        "  {} {}  {}",
+        "◉".cyan(),
+        title.bold(),
+        format!("{} results", found.len()).dimmed()
+    );
+    println!("  {}", "─".repeat(RULE).dimmed());
+
+    for result in &found {
+        print_summary(&result.summary);
+    }
+
+    println!();
+    println!(
+        "  {}",
+        "recall summaries --tags  to see every tag and intent".dimmed()
+    );
+    println!();
+    Ok(())
+}
+
+/// `recall summaries --tags` — the labels in use, so there is something to
+/// filter by.
+pub fn run_labels() -> Result<()> {
+    let conn = crate::db::schema::open_db()?;
+    let tags = queries::summary_tags(&conn)?;
+    let intents = queries::summary_intents(&conn)?;
+
+    if tags.is_empty() && intents.is_empty() {
+        println!(
+            "\n  {} {}\n",
+            "●".dimmed(),
+            "No tagged summaries yet. Run `recall summarize` first.".dimmed()
+        );
+        return Ok(());
+    }
+
+    println!();
+    for (heading, labels, flag) in [("Intents", &intents, "--intent"), ("Tags", &tags, "--tag")] {
+        if labels.is_empty() {
+            continue;
+        }
+        println!("  {} {}", "◉".cyan(), heading.bold());
+        println!("  {}", "─".repeat(RULE).dimmed());
+        for (label, count) in labels.iter() {
+            println!("  {} {:<24} {}", "│".dimmed(), label, count.to_string().dimmed());
+        }
+        println!(
+            "  {}",
+            format!("recall summaries {} <name>", flag).dimmed()
+        );
+        println!();
+    }
+    Ok(())
+}
+
+/// The search as one line, flags included, for the heading.
+fn describe(opts: &Options) -> String {
+    let mut parts: Vec<String> = opts.query.iter().cloned().collect();
+    if let Some(tag) = &opts.tag {
+        parts.push(format!("tag:{}", tag));
+    }
+    if let Some(intent) = &opts.intent {
+        parts.push(format!("intent:{}", intent));
+    }
+    if let Some(since) = &opts.since {
+        parts.push(format!("since:{}", since));
+    }
+    if let Some(until) = &opts.until {
+        parts.push(format!("until:{}", until));
+    }
+    parts.join(" ")
+}
+
+/// One summary: when, what kind of work, the text, and its tags.
+pub fn print_summary(summary: &Summary) {
+    let start = summary.started_at.unwrap_or(summary.created_at);
+    let span = match summary.ended_at {
+        Some(end) if end > start => format!("{}–{}", when(start, "%Y-%m-%d %H:%M"), when(end, "%H:%M")),
+        _ => when(start, "%Y-%m-%d %H:%M"),
+    };
+    let intent = summary
+        .intent
+        .as_deref()
+        .filter(|intent| !intent.is_empty() && *intent != "unknown")
+        .unwrap_or("");
+    println!(
+        "\n  {} {}  {}  {}",
+        "┌".dimmed(),
+        span.white().bold(),
+        intent.yellow(),
+        short(&summary.session_id).dimmed()
+    );
+    for line in wrap(&summary.summary_text, WIDTH) {
+        println!("  {} {}", "│".dimmed(), line);
+    }
+    let tags = summary.tag_list();
+    if tags.is_empty() {
+        println!("  {}", "└".dimmed());
+    } else {
+        let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+        println!("  {} {}", "└".dimmed(), tags.join(" ").blue());
+    }
+}
+
+fn when(ms: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|dt| dt.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_default()
+}
+
+fn short(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
+/// Break text into lines of at most `width` characters, at spaces.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines().filter(|line| !line.trim().is_empty()) {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let needed = line.chars().count() + usize::from(!line.is_empty()) + word.chars().count();
+            if !line.is_empty() && needed > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
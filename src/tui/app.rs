@@ -20,8 +20,12 @@ use crate::ai::models::{AiSession, Source};
 use crate::ai::resume::{self, CommandSpec};
 use crate::ai::search as ai_search;
 use crate::ai::store::{self as ai_store, Filter};
-use crate::db::models::{Command, Session};
+use crate::db::models::{Command, Session, Summary};
 use crate::db::queries;
+use crate::db::work_sessions;
+use crate::search::query::{self as search_query, Query};
+use crate::search::suggest;
+use crate::search::unified::{self, Hit};
 
 use super::home;
 
@@ -31,6 +35,8 @@ const LIST_LIMIT: usize = 300;
 const SHELL_SESSION_LIMIT: usize = 200;
 /// Per-source budget, so no one tool can crowd the others out of the list.
 const SOURCE_LIMIT: usize = 120;
+/// Hits taken from each corpus when searching.
+const SEARCH_LIMIT: usize = 300;
 
 // ─── Data ───────────────────────────────────────────────────
 
@@ -48,16 +54,14 @@ pub enum Entry {
         failures: usize,
         repos: Vec<String>,
         snippet: String,
+        rank: f64,
     },
 }
 
 impl Entry {
     pub fn kind(&self) -> Kind {
         match self {
-            Entry::Agent { session, .. } => match session.source {
-                Source::Claude => Kind::Claude,
-                Source::Codex => Kind::Codex,
-            },
+            Entry::Agent { session, .. } => Kind::of(session.source),
             Entry::Shell { .. } => Kind::Shell,
         }
     }
@@ -69,12 +73,11 @@ impl Entry {
         }
     }
 
-    /// Lower is a better match. Shell hits have no BM25 score of their own, so
-    /// they sort after agent hits when ranking by relevance.
+    /// Lower is a better match. Search results carry their negated fused
+    /// score, so every kind ranks on the same scale.
     pub fn rank(&self) -> f64 {
         match self {
-            Entry::Agent { rank, .. } => *rank,
-            Entry::Shell { .. } => 0.0,
+            Entry::Agent { rank, .. } | Entry::Shell { rank, .. } => *rank,
         }
     }
 
@@ -117,16 +120,41 @@ impl Entry {
 pub enum Kind {
     Claude,
     Codex,
+    Gemini,
+    Aider,
+    /// Imported web chats and every source configured in `[[sources]]`,
+    /// together.
+    Other,
     Shell,
 }
 
 impl Kind {
-    pub const ALL: [Kind; 3] = [Kind::Claude, Kind::Codex, Kind::Shell];
+    pub const ALL: [Kind; 6] = [
+        Kind::Claude,
+        Kind::Codex,
+        Kind::Gemini,
+        Kind::Aider,
+        Kind::Other,
+        Kind::Shell,
+    ];
+
+    pub fn of(source: Source) -> Kind {
+        match source {
+            Source::Claude => Kind::Claude,
+            Source::Codex => Kind::Codex,
+            Source::Gemini => Kind::Gemini,
+            Source::Aider => Kind::Aider,
+            Source::Chat | Source::Custom(_) => Kind::Other,
+        }
+    }
 
     pub fn tag(&self) -> &'static str {
         match self {
             Kind::Claude => "claude",
             Kind::Codex => "codex",
+            Kind::Gemini => "gemini",
+            Kind::Aider => "aider",
+            Kind::Other => "other",
             Kind::Shell => "shell",
         }
     }
@@ -135,6 +163,9 @@ impl Kind {
         match self {
             Kind::Claude => "Claude Code",
             Kind::Codex => "Codex",
+            Kind::Gemini => "Gemini CLI",
+            Kind::Aider => "Aider",
+            Kind::Other => "Other",
             Kind::Shell => "Shell",
         }
     }
@@ -145,6 +176,9 @@ impl Kind {
         match self {
             Kind::Claude => Color::Magenta,
             Kind::Codex => Color::Blue,
+            Kind::Gemini => Color::Cyan,
+            Kind::Aider => Color::LightRed,
+            Kind::Other => Color::Yellow,
             Kind::Shell => Color::Green,
         }
     }
@@ -259,21 +293,30 @@ pub struct App {
     pub focus: Focus,
     pub preview: Preview,
     pub preview_scroll: usize,
+    /// What `recall summarize` wrote about the selected shell work session.
+    pub summary: Option<Summary>,
     pub counts: Vec<(Kind, usize)>,
     pub total_commands: usize,
     pub total_agent_sessions: usize,
     /// Transient note shown in the status bar, e.g. after a manual refresh.
     pub status: Option<String>,
+    /// A corrected spelling of a search that found nothing, for Ctrl+Y.
+    pub suggestion: Option<String>,
+    /// Search the corrected spelling straight away instead of offering it.
+    autocorrect: bool,
     pub show_help: bool,
     pub help_scroll: usize,
     pub resume_dialog: Option<ResumeDialog>,
     pub should_quit: bool,
     /// Set when the user confirms a resume: the TUI exits and hands over the terminal.
     pub pending_resume: Option<CommandSpec>,
+    /// Idle time that splits a shell session into separate rows.
+    idle_gap_ms: i64,
 }
 
 impl App {
     pub fn new(conn: &Connection) -> Result<Self> {
+        let config = crate::config::settings::load_config().unwrap_or_default();
         let mut app = Self {
             all_entries: Vec::new(),
             entries: Vec::new(),
@@ -290,16 +333,21 @@ impl App {
             focus: Focus::Search,
             preview: Preview::Empty,
             preview_scroll: 0,
+            summary: None,
             counts: Vec::new(),
             total_commands: queries::get_all_commands(conn, 1_000_000)?.len(),
             total_agent_sessions: ai_store::stats(conn)?.sessions,
             status: None,
+            suggestion: None,
+            autocorrect: false,
             show_help: false,
             help_scroll: 0,
             resume_dialog: None,
             should_quit: false,
             pending_resume: None,
+            idle_gap_ms: config.sessions.idle_gap_ms(),
         };
+        app.autocorrect = config.search.autocorrect;
         app.refresh(conn)?;
         Ok(app)
     }
@@ -375,6 +423,14 @@ impl App {
                 self.sort = self.sort.toggled();
                 return self.refresh(conn);
             }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(corrected) = self.suggestion.take() {
+                    self.cursor = corrected.len();
+                    self.input = corrected;
+                    return self.refresh(conn);
+                }
+                return Ok(());
+            }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.input.clear();
                 self.cursor = 0;
@@ -488,7 +544,7 @@ impl App {
             KeyCode::Char('G') | KeyCode::End => {
                 self.select_to(conn, self.rows.len().saturating_sub(1), visible)?
             }
-            KeyCode::Char(c @ '1'..='4') => {
+            KeyCode::Char(c @ '1'..='7') => {
                 return self.select_tab(conn, c as usize - '1' as usize);
             }
             KeyCode::Char('/') => self.focus = Focus::Search,
@@ -518,7 +574,7 @@ impl App {
             KeyCode::Char('d') => {
                 self.preview_scroll = (self.preview_scroll + visible).min(self.preview_len())
             }
-            KeyCode::Char(c @ '1'..='4') => {
+            KeyCode::Char(c @ '1'..='7') => {
                 return self.select_tab(conn, c as usize - '1' as usize);
             }
             KeyCode::Char('/') => self.focus = Focus::Search,
@@ -604,13 +660,41 @@ impl App {
 
     /// Re-query every source for the current search, then apply the active tab.
     fn refresh(&mut self, conn: &Connection) -> Result<()> {
-        let query = self.input.trim().to_string();
+        let query = match search_query::parse(&self.input) {
+            Ok(query) => query,
+            // Say what is wrong with the filter and keep the last results up.
+            Err(e) => {
+                self.status = Some(e.to_string());
+                return Ok(());
+            }
+        };
 
-        let mut entries = self.agent_entries(conn, &query)?;
-        entries.extend(self.shell_entries(conn, &query)?);
+        self.suggestion = None;
+        let mut entries = if query.is_empty() {
+            let mut entries = self.agent_entries(conn)?;
+            entries.extend(self.shell_entries(conn, None)?);
+            entries
+        } else {
+            self.search_entries(conn, &query)?
+        };
+
+        // Nothing at all: perhaps a typo.
+        if entries.is_empty() && query.has_text() {
+            match suggest::suggest(conn, &self.input, &query)? {
+                Some(correction) if self.autocorrect => {
+                    entries = self.search_entries(conn, &search_query::parse(&correction.input)?)?;
+                    self.status = Some(format!("nothing for these words — showing \"{}\"", correction.input));
+                }
+                Some(correction) => {
+                    self.status = Some(format!("no matches — did you mean \"{}\"?  Ctrl+Y searches for it", correction.words()));
+                    self.suggestion = Some(correction.input);
+                }
+                None => {}
+            }
+        }
 
         match self.sort {
-            Sort::Newest => entries.sort_by(|a, b| b.last_activity().cmp(&a.last_activity())),
+            Sort::Newest => entries.sort_by_key(|e| std::cmp::Reverse(e.last_activity())),
             Sort::BestMatch => entries.sort_by(|a, b| {
                 a.rank()
                     .partial_cmp(&b.rank())
@@ -659,7 +743,7 @@ impl App {
         Ok(())
     }
 
-    /// Move `delta` tabs through All → Claude → Codex → Shell → All.
+    /// Move `delta` tabs through All → Claude → Codex → Gemini → Aider → Other → Shell → All.
     pub fn cycle_tab(&mut self, conn: &Connection, delta: isize) -> Result<()> {
         let current = self.tab_index() as isize;
         let count = Kind::ALL.len() as isize + 1;
@@ -667,7 +751,7 @@ impl App {
         self.select_tab(conn, next as usize)
     }
 
-    /// Tab 0 is "everything"; 1..=3 are the individual kinds.
+    /// Tab 0 is "everything"; 1..=6 are the individual kinds.
     pub fn select_tab(&mut self, conn: &Connection, index: usize) -> Result<()> {
         self.kind_filter = if index == 0 {
             None
@@ -734,105 +818,136 @@ impl App {
         self.kind_filter.is_none_or(|filter| filter == kind)
     }
 
-    fn agent_entries(&mut self, conn: &Connection, query: &str) -> Result<Vec<Entry>> {
-        // Query each source on its own budget. A single combined query would let
-        // whichever tool the user leans on bury the other one entirely.
+    /// The newest sessions of each agent tool, for browsing.
+    fn agent_entries(&mut self, conn: &Connection) -> Result<Vec<Entry>> {
+        // Each source on its own budget, so whichever tool the user leans on
+        // cannot bury the others entirely.
         let mut entries = Vec::new();
         self.mode = ai_search::Mode::Fts;
 
-        for source in [Source::Claude, Source::Codex] {
+        for source in Source::all() {
             let filter = Filter {
                 source: Some(source),
-                project: None,
                 limit: SOURCE_LIMIT,
+                ..Default::default()
             };
-
-            if query.is_empty() {
-                entries.extend(ai_store::list_sessions(conn, &filter)?.into_iter().map(
-                    |session| Entry::Agent {
-                        snippet: format!(
-                            "{} messages · {}",
-                            session.message_count, session.project
-                        ),
+            entries.extend(
+                ai_store::list_sessions(conn, &filter)?
+                    .into_iter()
+                    .map(|session| Entry::Agent {
+                        snippet: message_count_line(&session),
                         session,
                         rank: 0.0,
-                    },
-                ));
-                continue;
-            }
+                    }),
+            );
+        }
 
-            let (results, mode) = ai_search::search(conn, query, &filter, ai_search::Mode::Fts)?;
-            // Report fuzzy as soon as either source needed the fallback.
-            if mode == ai_search::Mode::Fuzzy && !results.is_empty() {
-                self.mode = mode;
+        Ok(entries)
+    }
+
+    /// One search across every corpus, ranked once, then shaped into rows:
+    /// agent hits as they are, shell hits gathered into their work sessions.
+    fn search_entries(&mut self, conn: &Connection, query: &Query) -> Result<Vec<Entry>> {
+        let found = unified::search(conn, query, SEARCH_LIMIT)?;
+        self.mode = found.agent_mode;
+
+        let mut entries = Vec::new();
+        let mut shell = ShellHits::default();
+        for ranked in found.hits {
+            // Entries rank lower-is-better, like BM25.
+            let rank = -ranked.score;
+            match ranked.hit {
+                Hit::Agent(result) => entries.push(Entry::Agent {
+                    snippet: if result.snippet.is_empty() {
+                        message_count_line(&result.session)
+                    } else {
+                        result.snippet.replace('\n', " ")
+                    },
+                    rank,
+                    session: result.session,
+                }),
+                Hit::Command(result) => {
+                    shell.sessions.insert(result.command.session_id.clone());
+                    if let Some(id) = result.command.id {
+                        shell.commands.insert(id, (result.command.command_text, rank));
+                    }
+                }
+                Hit::Summary(result) => {
+                    shell.sessions.insert(result.summary.session_id.clone());
+                    shell.summaries.push((result.summary, rank));
+                }
             }
-            entries.extend(results.into_iter().map(|result| Entry::Agent {
-                snippet: result.snippet.replace('\n', " "),
-                rank: result.rank,
-                session: result.session,
-            }));
         }
 
+        entries.extend(self.shell_entries(conn, Some(&shell))?);
         Ok(entries)
     }
 
-    fn shell_entries(&self, conn: &Connection, query: &str) -> Result<Vec<Entry>> {
-        // Searching narrows to the sessions whose commands matched; browsing
-        // shows the most recent sessions.
-        let (sessions, matched) = if query.is_empty() {
-            (queries::get_sessions(conn, SHELL_SESSION_LIMIT, 0)?, None)
-        } else {
-            let mut best: std::collections::HashMap<String, String> =
-                std::collections::HashMap::new();
-            for hit in queries::search_commands(conn, query, 300)? {
-                best.entry(hit.command.session_id.clone())
-                    .or_insert(hit.command.command_text);
-            }
-            let kept: Vec<Session> = queries::get_sessions(conn, 1000, 0)?
+    /// Shell work sessions: the most recent when browsing, or those holding a
+    /// search hit, each ranked by its best one.
+    fn shell_entries(&self, conn: &Connection, matched: Option<&ShellHits>) -> Result<Vec<Entry>> {
+        let sessions = match matched {
+            None => queries::get_sessions(conn, SHELL_SESSION_LIMIT, 0)?,
+            Some(hits) => queries::get_sessions(conn, 1000, 0)?
                 .into_iter()
-                .filter(|s| best.contains_key(&s.id))
-                .collect();
-            (kept, Some(best))
+                .filter(|s| hits.sessions.contains(&s.id))
+                .collect(),
         };
 
+        // One row per work session: a terminal open for days shows up as the
+        // separate stretches of work it held.
         let mut entries = Vec::new();
         for session in sessions {
             let commands = queries::get_session_commands(conn, &session.id)?;
-            if commands.is_empty() {
-                continue;
-            }
-
-            let failures = commands
-                .iter()
-                .filter(|c| c.exit_code.is_some_and(|code| code != 0))
-                .count();
-            let mut repos: Vec<String> = commands
-                .iter()
-                .filter_map(|c| c.git_repo.clone())
-                .map(|r| r.rsplit('/').next().unwrap_or(&r).to_string())
-                .collect();
-            repos.sort();
-            repos.dedup();
-
-            let last = commands.iter().map(|c| c.timestamp).max();
-            let snippet = match &matched {
-                Some(best) => best.get(&session.id).cloned().unwrap_or_default(),
-                None => commands
-                    .last()
-                    .map(|c| c.command_text.clone())
-                    .unwrap_or_default(),
-            };
+            let works = work_sessions::split(&commands, self.idle_gap_ms);
+            let last_index = works.len().saturating_sub(1);
+
+            for (index, work) in works.into_iter().enumerate() {
+                let (snippet, rank) = match matched {
+                    Some(hits) => match hits.best_in(&session.id, work) {
+                        Some(best) => best,
+                        None => continue,
+                    },
+                    None => (
+                        work.last().map(|c| c.command_text.clone()).unwrap_or_default(),
+                        0.0,
+                    ),
+                };
 
-            entries.push(Entry::Shell {
-                session: Session {
-                    end_time: last.or(session.end_time),
-                    ..session
-                },
-                command_count: commands.len(),
-                failures,
-                repos,
-                snippet,
-            });
+                let failures = work
+                    .iter()
+                    .filter(|c| c.exit_code.is_some_and(|code| code != 0))
+                    .count();
+                let mut repos: Vec<String> = work
+                    .iter()
+                    .filter_map(|c| c.git_repo.clone())
+                    .map(|r| r.rsplit('/').next().unwrap_or(&r).to_string())
+                    .collect();
+                repos.sort();
+                repos.dedup();
+
+                let finished = work_sessions::finished_at(work);
+                // The shell's own exit only closes its last stretch of work,
+                // and only when it exited soon after.
+                let end_time = match (index == last_index, session.end_time, finished) {
+                    (true, Some(exit), Some(done)) if exit - done <= self.idle_gap_ms => Some(exit),
+                    _ => finished.or(session.end_time),
+                };
+
+                entries.push(Entry::Shell {
+                    session: Session {
+                        start_time: work[0].timestamp,
+                        end_time,
+                        initial_dir: work[0].cwd.clone().or(session.initial_dir.clone()),
+                        ..session.clone()
+                    },
+                    command_count: work.len(),
+                    failures,
+                    repos,
+                    snippet,
+                    rank,
+                });
+            }
         }
 
         Ok(entries)
@@ -914,6 +1029,15 @@ impl App {
 
     fn load_preview(&mut self, conn: &Connection) -> Result<()> {
         self.preview_scroll = 0;
+        self.summary = match self.selected_entry() {
+            Some(Entry::Shell { session, .. }) => queries::summary_for(
+                conn,
+                &session.id,
+                session.start_time,
+                session.end_time.unwrap_or(i64::MAX),
+            )?,
+            _ => None,
+        };
         self.preview = match self.selected_entry() {
             Some(Entry::Agent { session, .. }) => Preview::Transcript(
                 ai_store::session_chunks(conn, &session.uid)?
@@ -922,7 +1046,14 @@ impl App {
                     .collect(),
             ),
             Some(Entry::Shell { session, .. }) => {
-                Preview::Commands(queries::get_session_commands(conn, &session.id)?)
+                // Just this work session's commands, not the whole shell's.
+                let end = session.end_time.unwrap_or(i64::MAX);
+                Preview::Commands(
+                    queries::get_session_commands(conn, &session.id)?
+                        .into_iter()
+                        .filter(|c| c.timestamp >= session.start_time && c.timestamp <= end)
+                        .collect(),
+                )
             }
             None => Preview::Empty,
         };
@@ -961,14 +1092,20 @@ impl App {
             match self.mode {
                 ai_search::Mode::Fts => "Full-text",
                 ai_search::Mode::Fuzzy => "Fuzzy",
+                ai_search::Mode::Hybrid => "Hybrid",
             }
         }
     }
 
-    /// Whitespace-separated query terms, for highlighting matches.
+    /// The words being searched for, for highlighting matches. Filters and
+    /// exclusions are left out: they never appear in the text.
     pub fn query_terms(&self) -> Vec<String> {
-        self.input
-            .split_whitespace()
+        let terms = search_query::parse(&self.input)
+            .map(|query| query.terms)
+            .unwrap_or_default();
+        terms
+            .iter()
+            .flat_map(|term| term.split_whitespace())
             .filter(|term| term.len() > 1)
             .map(|term| term.to_lowercase())
             .collect()
@@ -977,6 +1114,47 @@ impl App {
 
 // ─── Helpers ────────────────────────────────────────────────
 
+/// Search hits on the shell side, waiting to be placed in work sessions.
+#[derive(Default)]
+struct ShellHits {
+    /// Command id to its text and rank.
+    commands: std::collections::HashMap<i64, (String, f64)>,
+    summaries: Vec<(Summary, f64)>,
+    sessions: std::collections::HashSet<String>,
+}
+
+impl ShellHits {
+    /// The best hit within one work session: a matching command, or a
+    /// summary of that stretch of work.
+    fn best_in(&self, session_id: &str, work: &[Command]) -> Option<(String, f64)> {
+        let (first, last) = (work.first()?.timestamp, work.last()?.timestamp);
+        let commands = work
+            .iter()
+            .filter_map(|c| self.commands.get(&c.id?))
+            .map(|(text, rank)| (text.clone(), *rank));
+        let summaries = self
+            .summaries
+            .iter()
+            .filter(|(summary, _)| summary.session_id == session_id)
+            .filter(|(summary, _)| match (summary.started_at, summary.ended_at) {
+                (Some(start), Some(end)) => first <= end && last >= start,
+                // Older summaries cover the whole shell session.
+                _ => true,
+            })
+            .map(|(summary, rank)| {
+                let line = summary.summary_text.lines().next().unwrap_or("");
+                (format!("✎ {}", line), *rank)
+            });
+        commands
+            .chain(summaries)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+fn message_count_line(session: &AiSession) -> String {
+    format!("{} messages · {}", session.message_count, session.project)
+}
+
 /// Trim to `limit` while keeping every kind represented. Agent sessions usually
 /// outnumber shell sessions, and a plain newest-first cut would drop shell
 /// results out of the list entirely.
@@ -1043,9 +1221,11 @@ pub fn run_tui() -> Result<()> {
     // under a second; a cold one is only slow on a machine that has never
     // indexed, so say something before it starts.
     if ai_store::stats(&conn)?.sessions == 0 {
-        println!("  Indexing Claude Code and Codex transcripts for the first time...");
+        println!("  Indexing agent transcripts for the first time...");
     }
     indexer::index_all(&conn, false)?;
+    // Housekeeping is not worth refusing to open over.
+    let _ = crate::gc::run_if_due(&conn);
 
     let mut app = App::new(&conn)?;
 
@@ -1110,6 +1290,9 @@ mod tests {
             unsafe {
                 std::env::set_var("RECALL_CLAUDE_DIR", &empty);
                 std::env::set_var("RECALL_CODEX_DIR", &empty);
+                std::env::set_var("RECALL_GEMINI_DIR", &empty);
+                std::env::set_var("RECALL_AIDER_ROOTS", &empty);
+                std::env::set_var("RECALL_IMPORTS_DIR", &empty);
             }
         });
     }
@@ -1321,6 +1504,37 @@ mod tests {
         assert!(app.selected_entry().is_some());
     }
 
+    #[test]
+    fn a_summarized_shell_session_brings_its_summary_to_the_details() {
+        let (mut app, conn) = test_app();
+        conn.execute("INSERT INTO sessions (id, start_time) VALUES ('s1', 600)", [])
+            .unwrap();
+        queries::insert_summary(
+            &conn,
+            &Summary {
+                id: None,
+                session_id: "s1".into(),
+                summary_text: "Bisected the slow build".into(),
+                tags: Some(r#"["git"]"#.into()),
+                intent: Some("debugging".into()),
+                created_at: 700,
+                started_at: None,
+                ended_at: None,
+            },
+        )
+        .unwrap();
+        app.all_entries = vec![agent_entry("a1", 900), shell_entry("s1", 600)];
+        app.counts = vec![(Kind::Claude, 1), (Kind::Codex, 0), (Kind::Shell, 1)];
+        app.apply_tab(&conn).unwrap();
+        assert!(app.summary.is_none(), "agent sessions have none");
+
+        while !matches!(app.selected_entry(), Some(Entry::Shell { .. })) {
+            press(&mut app, &conn, KeyCode::Down, KeyModifiers::NONE);
+        }
+        let summary = app.summary.as_ref().expect("the shell session's summary");
+        assert_eq!(summary.summary_text, "Bisected the slow build");
+    }
+
     #[test]
     fn group_headers_are_selectable_rows() {
         let (app, _conn) = app_with_groups();
@@ -1377,6 +1591,27 @@ mod tests {
         assert_eq!(app.input, "", "the binding is not typed");
     }
 
+    #[test]
+    fn a_misspelled_search_offers_a_correction_to_accept() {
+        let (mut app, conn) = test_app();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, start_time) VALUES ('s', 0);
+             INSERT INTO commands (session_id, command_text, timestamp)
+                 VALUES ('s', 'kubectl get pods -n kubernetes', 0);",
+        )
+        .unwrap();
+
+        typed(&mut app, &conn, "kuberntes");
+        assert!(app.entries.is_empty());
+        assert!(app.status.as_deref().unwrap_or("").contains("\"kubernetes\""));
+
+        press(&mut app, &conn, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert_eq!(app.input, "kubernetes");
+        assert_eq!(app.cursor, app.input.len());
+        assert_eq!(app.entries.len(), 1);
+        assert!(app.suggestion.is_none());
+    }
+
     #[test]
     fn the_status_note_clears_on_the_next_keystroke() {
         let (mut app, conn) = test_app();
@@ -1392,13 +1627,13 @@ mod tests {
         let (mut app, conn) = test_app();
         assert_eq!(app.tab_index(), 0);
 
-        for expected in [1, 2, 3, 0] {
+        for expected in [1, 2, 3, 4, 5, 6, 0] {
             app.cycle_tab(&conn, 1).unwrap();
             assert_eq!(app.tab_index(), expected);
         }
 
         app.cycle_tab(&conn, -1).unwrap();
-        assert_eq!(app.tab_index(), 3, "wraps backwards to the last tab");
+        assert_eq!(app.tab_index(), 6, "wraps backwards to the last tab");
     }
 
     #[test]
@@ -1448,6 +1683,47 @@ mod tests {
         assert_eq!(scroll, 1, "scrolls back up to the selection");
     }
 
+    #[test]
+    fn a_work_session_shows_its_best_hit_command_or_summary() {
+        let command = |id: i64, at: i64| Command {
+            id: Some(id),
+            session_id: "s".into(),
+            command_text: format!("command {}", id),
+            timestamp: at,
+            duration_ms: None,
+            cwd: None,
+            git_repo: None,
+            git_branch: None,
+            exit_code: Some(0),
+            output: None,
+        };
+        let summary = |span: (i64, i64)| Summary {
+            id: None,
+            session_id: "s".into(),
+            summary_text: format!("worked from {}", span.0),
+            tags: None,
+            intent: None,
+            created_at: 0,
+            started_at: Some(span.0),
+            ended_at: Some(span.1),
+        };
+        let mut hits = ShellHits::default();
+        hits.commands.insert(1, ("command 1".into(), -0.010));
+        hits.summaries.push((summary((100, 200)), -0.016));
+        hits.summaries.push((summary((5000, 6000)), -0.5));
+
+        let morning = [command(1, 100), command(2, 200)];
+        assert_eq!(
+            hits.best_in("s", &morning),
+            Some(("✎ worked from 100".to_string(), -0.016))
+        );
+        let afternoon = [command(3, 5000)];
+        assert_eq!(hits.best_in("s", &afternoon).unwrap().1, -0.5);
+        let evening = [command(4, 9000)];
+        assert_eq!(hits.best_in("s", &evening), None, "no hit in this stretch");
+        assert_eq!(hits.best_in("other", &afternoon), None, "another shell's summary");
+    }
+
     fn shell_entry(id: &str, at: i64) -> Entry {
         Entry::Shell {
             session: Session {
@@ -1461,6 +1737,7 @@ mod tests {
             failures: 0,
             repos: vec![],
             snippet: String::new(),
+            rank: 0.0,
         }
     }
 
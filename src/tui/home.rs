@@ -4,7 +4,8 @@
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
-use crate::db::models::Command;
+use crate::ai::models::{split_matches, MATCH_START};
+use crate::db::models::{Command, Summary};
 
 use super::app::{App, Entry, Focus, Kind, Preview, Row};
 
@@ -230,6 +231,17 @@ fn header_row(
     .style(row)
 }
 
+/// The short source name a row starts with. Imported chats and configured
+/// sources share the Other tab, so each row says which one it is.
+fn row_tag(entry: &Entry) -> String {
+    match entry {
+        Entry::Agent { session, .. } if entry.kind() == Kind::Other => {
+            session.source.as_str().chars().take(7).collect()
+        }
+        _ => entry.kind().tag().to_string(),
+    }
+}
+
 fn item_row(
     entry: &Entry,
     selected: bool,
@@ -256,7 +268,7 @@ fn item_row(
     let mut spans = vec![
         edge(selected, EDGE_LEFT),
         Span::styled(
-            format!(" {:<7}", kind.tag()),
+            format!(" {:<7}", row_tag(entry)),
             Style::default().fg(kind.color()),
         ),
         Span::styled(
@@ -349,7 +361,8 @@ fn render_details(frame: &mut Frame, area: Rect, app: &App) {
 
     let searching = !app.input.trim().is_empty();
     let named = matches!(entry, Entry::Agent { session, .. } if session.custom_name.is_some());
-    let header_height = 6 + u16::from(searching) * 2 + u16::from(named);
+    let summarized = matches!(entry, Entry::Shell { .. }) && app.summary.is_some();
+    let header_height = 6 + u16::from(searching) * 2 + u16::from(named) + u16::from(summarized) * 2;
 
     let sections = Layout::default()
         .direction(Direction::Vertical)
@@ -367,8 +380,9 @@ fn render_details(frame: &mut Frame, area: Rect, app: &App) {
             " Match:     ",
             Style::default().fg(DIM),
         )];
-        spans.extend(highlight(
-            &truncate(entry.snippet(), 88),
+        spans.extend(highlight_snippet(
+            entry.snippet(),
+            88,
             &app.query_terms(),
             Style::default().fg(TEXT),
         ));
@@ -476,7 +490,7 @@ fn detail_fields(app: &App, entry: &Entry) -> Vec<Line<'static>> {
 
     match entry {
         Entry::Agent { session, .. } => {
-            let mut lines = vec![field("Source", kind.label().to_string(), kind.color())];
+            let mut lines = vec![field("Source", session.source.label().to_string(), kind.color())];
 
             // Show the saved name in its own right, so the conversation's own
             // opening line is still visible underneath it.
@@ -523,7 +537,8 @@ fn detail_fields(app: &App, entry: &Entry) -> Vec<Line<'static>> {
             failures,
             repos,
             ..
-        } => vec![
+        } => {
+            let mut lines = vec![
             field("Source", "Shell".to_string(), kind.color()),
             field("Session", short_id(&session.id), TEXT),
             field(
@@ -560,10 +575,35 @@ fn detail_fields(app: &App, entry: &Entry) -> Vec<Line<'static>> {
                     Style::default().fg(Color::Red),
                 ),
             ]),
-        ],
+            ];
+            if let Some(summary) = &app.summary {
+                lines.extend(summary_fields(summary));
+            }
+            lines
+        }
     }
 }
 
+/// The summary of a shell work session: its text on one line, then its
+/// intent and tags.
+fn summary_fields(summary: &Summary) -> Vec<Line<'static>> {
+    let text = summary.summary_text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut labels: Vec<Span<'static>> = vec![Span::styled(format!(" {:<10}", "Tags:"), Style::default().fg(DIM))];
+    if let Some(intent) = summary.intent.as_deref().filter(|i| !i.is_empty() && *i != "unknown") {
+        labels.push(Span::styled(format!("{}   ", intent), Style::default().fg(HIGHLIGHT)));
+    }
+    let tags = summary.tag_list();
+    labels.push(Span::styled(
+        if tags.is_empty() {
+            "—".to_string()
+        } else {
+            truncate(&tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "), 60)
+        },
+        Style::default().fg(TEXT),
+    ));
+    vec![field("Summary", truncate(&text, 88), TEXT), Line::from(labels)]
+}
+
 fn render_content(frame: &mut Frame, area: Rect, app: &App) {
     let height = area.height as usize;
     let terms = app.query_terms();
@@ -788,8 +828,9 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         ("", "FINDING"),
         ("(type)", "filter agent conversations and shell commands together"),
         ("Ctrl+U", "clear the query"),
-        ("Shift+← / Shift+→", "switch source tab: All / Claude Code / Codex / Shell"),
-        ("1 2 3 4", "jump straight to a source tab (outside the search box)"),
+        ("Ctrl+Y", "search for the suggested spelling when nothing matched"),
+        ("Shift+← / Shift+→", "switch source tab: All / Claude Code / Codex / Gemini CLI / Aider / Other / Shell"),
+        ("1 – 7", "jump straight to a source tab (outside the search box)"),
         ("Ctrl+S", "next source tab"),
         ("Ctrl+G", "group by source, or show one flat newest-first list"),
         ("Ctrl+O", "sort by newest or by best match"),
@@ -805,7 +846,7 @@ fn render_help_overlay(frame: &mut Frame, area: Rect, app: &App) {
         ("", ""),
         ("", "NOTES"),
         ("", "Full-text search falls back to substring matching automatically."),
-        ("", "Resuming hands the terminal to claude or codex, then exits."),
+        ("", "Resuming hands the terminal to the session's own tool, then exits."),
     ];
 
     let lines: Vec<Line> = entries
@@ -856,15 +897,12 @@ fn render_resume_dialog(frame: &mut Frame, area: Rect, app: &App) {
     };
 
     let session = &dialog.session;
-    let kind = match session.source {
-        crate::ai::models::Source::Claude => Kind::Claude,
-        crate::ai::models::Source::Codex => Kind::Codex,
-    };
+    let kind = Kind::of(session.source);
 
     let mut lines = vec![
         Line::from(vec![
             Span::styled(
-                format!(" {} ", kind.label()),
+                format!(" {} ", session.source.label()),
                 Style::default().fg(kind.color()).bold(),
             ),
             Span::styled("· ", Style::default().fg(DIM)),
@@ -952,6 +990,35 @@ fn render_resume_dialog(frame: &mut Frame, area: Rect, app: &App) {
 // ─── Text helpers ───────────────────────────────────────────
 
 /// Split `text` so every occurrence of a query term is styled as a match.
+/// Matches are underlined in the terminal's own yellow. Underline carries
+/// the mark; the colour is a hint, so it stays readable even where yellow
+/// has poor contrast against the user's background.
+fn matched_style(base: Style) -> Style {
+    base.fg(HIGHLIGHT).add_modifier(Modifier::UNDERLINED)
+}
+
+/// A search snippet cut to `max` characters. Agent snippets carry the
+/// index's own match marks, which know about stemming and phrases; anything
+/// unmarked falls back to finding the query's words.
+fn highlight_snippet(text: &str, max: usize, terms: &[String], base: Style) -> Vec<Span<'static>> {
+    if !text.contains(MATCH_START) {
+        return highlight(&truncate(text, max), terms, base);
+    }
+
+    let mut left = max;
+    let mut spans = Vec::new();
+    for (piece, is_match) in split_matches(text) {
+        if left == 0 {
+            break;
+        }
+        let count = piece.chars().count();
+        let piece = if count > left { truncate(&piece, left) } else { piece };
+        left = left.saturating_sub(count);
+        spans.push(Span::styled(piece, if is_match { matched_style(base) } else { base }));
+    }
+    spans
+}
+
 fn highlight(text: &str, terms: &[String], base: Style) -> Vec<Span<'static>> {
     if terms.is_empty() {
         return vec![Span::styled(text.to_string(), base)];
@@ -971,10 +1038,7 @@ fn highlight(text: &str, terms: &[String], base: Style) -> Vec<Span<'static>> {
         }
     }
 
-    // Matches are underlined in the terminal's own yellow. Underline carries
-    // the mark; the colour is a hint, so it stays readable even where yellow
-    // has poor contrast against the user's background.
-    let matched = base.fg(HIGHLIGHT).add_modifier(Modifier::UNDERLINED);
+    let matched = matched_style(base);
     let mut spans = Vec::new();
     let mut buffer = String::new();
     let mut current = marks.first().copied().unwrap_or(false);
@@ -1045,6 +1109,7 @@ fn truncate(text: &str, max: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ai::models::MATCH_END;
 
     #[test]
     fn highlight_marks_every_occurrence() {
@@ -1057,6 +1122,20 @@ mod tests {
         assert_eq!(matched, vec!["retry", "retry"]);
     }
 
+    #[test]
+    fn snippet_marks_from_the_index_are_drawn_as_matches() {
+        let snippet = format!("the {}retries{} back off", MATCH_START, MATCH_END);
+        let spans = highlight_snippet(&snippet, 88, &["retry".to_string()], Style::default());
+        let matched: Vec<&str> = spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::UNDERLINED))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(matched, vec!["retries"], "the index's match, not the typed word");
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "the retries back off");
+    }
+
     #[test]
     fn highlight_keeps_the_weight_of_the_row_it_sits_on() {
         let base = Style::default().add_modifier(Modifier::BOLD);
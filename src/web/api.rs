@@ -8,8 +8,11 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
+use crate::ai::models;
 use crate::db::queries;
 use crate::db::schema::open_db;
+use crate::search::dates;
+use crate::search::unified::{self, Hit};
 
 pub fn routes() -> Router {
     Router::new()
@@ -21,6 +24,7 @@ pub fn routes() -> Router {
         .route("/api/graph", get(get_graph_data))
         .route("/api/stats", get(get_stats))
         .route("/api/search", get(search))
+        .route("/api/find", get(find))
 }
 
 async fn index_html() -> Html<&'static str> {
@@ -136,12 +140,28 @@ async fn get_stats() -> Result<Json<serde_json::Value>, StatusCode> {
 struct SearchQuery {
     q: String,
     limit: Option<usize>,
+    offset: Option<usize>,
+    /// Date phrases, as for `recall search --since/--until`.
+    since: Option<String>,
+    until: Option<String>,
 }
 
 async fn search(Query(sq): Query<SearchQuery>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let mut parsed = crate::search::query::parse(&sq.q).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let span = |text: &str| dates::parse(text).map_err(|_| StatusCode::BAD_REQUEST);
+    if let Some(since) = &sq.since {
+        parsed.after = Some(span(since)?.start);
+    }
+    if let Some(until) = &sq.until {
+        parsed.before = Some(span(until)?.end);
+    }
     let conn = open_db().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let results = queries::search_commands(&conn, &sq.q, sq.limit.unwrap_or(50))
+    let limit = sq.limit.unwrap_or(50);
+    let offset = sq.offset.unwrap_or(0);
+    let results = queries::find_commands(&conn, &parsed, limit, offset)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Where the next page starts, while pages keep coming back full.
+    let next_offset = (results.len() == limit).then_some(offset + limit);
 
     let commands: Vec<serde_json::Value> = results.iter().map(|r| {
         json!({
@@ -159,7 +179,57 @@ async fn search(Query(sq): Query<SearchQuery>) -> Result<Json<serde_json::Value>
         })
     }).collect();
 
-    Ok(Json(json!({ "results": commands })))
+    Ok(Json(json!({ "results": commands, "next_offset": next_offset })))
+}
+
+#[derive(Deserialize)]
+struct FindQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Commands, agent sessions and summaries in one ranked list, as `recall find`.
+async fn find(Query(fq): Query<FindQuery>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let parsed = crate::search::query::parse(&fq.q).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let conn = open_db().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let limit = fq.limit.unwrap_or(50);
+    let mut found = unified::search(&conn, &parsed, limit)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    found.hits.truncate(limit);
+
+    let hits: Vec<serde_json::Value> = found.hits.iter().map(|ranked| match &ranked.hit {
+        Hit::Command(r) => json!({
+            "kind": "command",
+            "score": ranked.score,
+            "id": r.command.id,
+            "session_id": r.command.session_id,
+            "command_text": r.command.command_text,
+            "timestamp": r.command.timestamp,
+            "cwd": r.command.cwd,
+            "git_repo": r.command.git_repo,
+            "exit_code": r.command.exit_code,
+        }),
+        Hit::Agent(r) => json!({
+            "kind": "agent",
+            "score": ranked.score,
+            "uid": r.session.uid,
+            "source": r.session.source.as_str(),
+            "session_id": r.session.session_id,
+            "title": r.session.title,
+            "project": r.session.project,
+            "timestamp": r.session.last_activity,
+            "snippet": models::strip_matches(&r.snippet),
+        }),
+        Hit::Summary(r) => json!({
+            "kind": "summary",
+            "score": ranked.score,
+            "session_id": r.summary.session_id,
+            "summary_text": r.summary.summary_text,
+            "timestamp": r.summary.ended_at.unwrap_or(r.summary.created_at),
+        }),
+    }).collect();
+
+    Ok(Json(json!({ "results": hits })))
 }
 
 async fn get_graph_data() -> Result<Json<serde_json::Value>, StatusCode> {
@@ -328,20 +328,41 @@ async function doSearch(q) {
     overlay.classList.remove('hidden');
 
     try {
-        const r = await fetch(`/api/search?q=${encodeURIComponent(q)}&limit=50`);
+        const r = await fetch(`/api/find?q=${encodeURIComponent(q)}&limit=50`);
         const d = await r.json();
         const results = d.results || [];
 
         qs('#search-count').textContent = `${results.length} result${results.length !== 1 ? 's' : ''} for \u201c${q}\u201d`;
 
         if (!results.length) {
-            list.innerHTML = `<div class="empty"><p>No commands match \u201c${esc(q)}\u201d</p></div>`;
+            list.innerHTML = `<div class="empty"><p>Nothing matches \u201c${esc(q)}\u201d</p></div>`;
             return;
         }
 
         let h = '';
         results.forEach(c => {
             const ts = shortDate(c.timestamp);
+            if (c.kind === 'agent') {
+                h += `<div class="sr-row">
+                    <span class="cmd-icon unk">\u25c6</span>
+                    <span class="cmd-ts">${ts}</span>
+                    <span class="s-dir" style="font-size:12px">${esc(lastSegment(c.project) || '')}</span>
+                    <span class="cmd-txt" style="font-size:13px">${esc(c.title || c.snippet || '(untitled)')}</span>
+                    <span><span class="tag repo">${esc(c.source)}</span></span>
+                </div>`;
+                return;
+            }
+            if (c.kind === 'summary') {
+                h += `<div class="sr-row" onclick="openSession('${esc(c.session_id)}')">
+                    <span class="cmd-icon unk">\u270e</span>
+                    <span class="cmd-ts">${ts}</span>
+                    <span class="s-dir" style="font-size:12px">summary</span>
+                    <span class="cmd-txt" style="font-size:13px">${esc((c.summary_text || '').split('\n')[0])}</span>
+                    <span></span>
+                </div>`;
+                return;
+            }
+
             const dir = lastSegment(c.cwd) || '';
             const isFail = c.exit_code != null && c.exit_code !== 0;
             let icon, cls;